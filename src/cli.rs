@@ -0,0 +1,118 @@
+use crate::utils::parse_int;
+use crate::viewer::{AddressMap, DataType, DisplayType, Endianness, FloatNotation, MAX_PRECISION};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// File to open, `-` or a pipe reads stdin and the file picker starts otherwise
+    pub file: Option<PathBuf>,
+
+    /// Byte offset to place the cursor at, e.g. 1024, 0x400 or 0x400*3
+    #[arg(short, long, value_parser = parse_offset_arg)]
+    pub offset: Option<usize>,
+
+    /// Address the file is loaded at, goto takes addresses prefixed with `@`
+    #[arg(short, long, value_parser = parse_offset_arg, conflicts_with = "map")]
+    pub base: Option<usize>,
+
+    /// Segments loaded at other addresses as OFFSET:LEN@ADDRESS, e.g. 0:0x4000@0x8000000,0x4000@0x20000000
+    #[arg(short, long, value_name = "SEGMENTS")]
+    pub map: Option<AddressMap>,
+
+    /// Keep reading bytes appended to the file, like `tail -f`
+    #[arg(short, long)]
+    pub follow: bool,
+
+    #[command(flatten)]
+    pub view: ViewArgs,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print the file as a table to stdout instead of opening the viewer
+    Dump(DumpArgs),
+    /// Rebuild a binary file from an xxd, hexdump -C or hexer dump
+    Undump(UndumpArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ViewArgs {
+    /// Data type of the cells: u8, i8, u16, i16, u32, i32, u64, i64, f32 or f64
+    #[arg(short = 't', long = "type", value_name = "TYPE")]
+    pub data_type: Option<DataType>,
+
+    /// How cells are displayed: dec, hex, oct or bin
+    #[arg(short, long)]
+    pub display: Option<DisplayType>,
+
+    /// Byte order of multi-byte cells: little or big
+    #[arg(short, long)]
+    pub endian: Option<Endianness>,
+
+    /// Notation of decimal floats: sci, fixed or hexfloat (C99 `%a`)
+    #[arg(long, value_name = "NOTATION")]
+    pub float: Option<FloatNotation>,
+
+    /// Digits after the point of decimal floats, 5 for f32 and 10 for f64 by default
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(..=MAX_PRECISION as i64))]
+    pub precision: Option<u8>,
+
+    /// Number of cells per row, defaults to the terminal width in the viewer and 16 bytes in dumps
+    #[arg(short, long, value_parser = clap::value_parser!(u16).range(1..))]
+    pub cols: Option<u16>,
+}
+
+#[derive(Debug, Args)]
+pub struct DumpArgs {
+    /// File to dump, `-` reads stdin
+    pub file: PathBuf,
+
+    /// Layout of the output, xxd and hexdump match `xxd -c COLS` and `hexdump -C` byte for byte
+    #[arg(short, long, value_enum, default_value_t = DumpFormat::Hexer)]
+    pub format: DumpFormat,
+
+    /// Byte offset to start at
+    #[arg(short, long, value_parser = parse_offset_arg, default_value_t = 0)]
+    pub seek: usize,
+
+    /// Number of bytes to dump, everything up to the end of the file when omitted
+    #[arg(short = 'n', long, value_parser = parse_offset_arg)]
+    pub length: Option<usize>,
+
+    #[command(flatten)]
+    pub view: ViewArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct UndumpArgs {
+    /// Dump to read, stdin when omitted or `-`
+    pub input: Option<PathBuf>,
+
+    /// File to write the bytes to instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Layout of the dump, detected from the first line when omitted
+    #[arg(short, long, value_enum)]
+    pub format: Option<DumpFormat>,
+
+    /// Fail when a line does not start where the previous one ended instead of zero filling
+    #[arg(long)]
+    pub strict: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DumpFormat {
+    Hexer,
+    Xxd,
+    Hexdump,
+}
+
+fn parse_offset_arg(input: &str) -> Result<usize, String> {
+    usize::try_from(parse_int(input)?).map_err(|_| String::from("offset is out of range"))
+}
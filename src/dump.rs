@@ -0,0 +1,297 @@
+use crate::cli::{DumpArgs, DumpFormat};
+use crate::viewer::{
+    ByteSource, DataType, DisplayType, Endianness, FloatNotation, format_float, group_nibbles,
+};
+use std::io::{BufWriter, ErrorKind, Result, Write, stdout};
+use std::path::Path;
+use std::str::FromStr;
+
+// Bytes per line of the hexer table when no column count is given
+const LINE_BYTES: usize = 16;
+const HEADER: &str = "# hexer dump:";
+
+// Settings of a hexer table, written as the first line so the dump can be read back
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Table {
+    pub data_type: DataType,
+    pub display_type: DisplayType,
+    pub endianness: Endianness,
+    pub cols: usize,
+    pub float_notation: FloatNotation,
+    pub precision: Option<usize>,
+}
+
+pub fn dump(args: &DumpArgs) -> Result<()> {
+    let source = match args.file == Path::new("-") {
+        true => ByteSource::stdin()?,
+        false => ByteSource::open(&args.file)?,
+    };
+    let bytes = source.read(args.seek, args.length.unwrap_or(usize::MAX));
+    let mut out = BufWriter::new(stdout().lock());
+    let result = match args.format {
+        DumpFormat::Hexer => {
+            let data_type = args.view.data_type.unwrap_or_default();
+            let table = Table {
+                data_type,
+                display_type: args.view.display.unwrap_or_default(),
+                endianness: args.view.endian.unwrap_or_default(),
+                cols: match args.view.cols {
+                    Some(cols) => cols as usize,
+                    None => (LINE_BYTES / data_type.size()).max(1),
+                },
+                float_notation: args.view.float.unwrap_or_default(),
+                precision: args.view.precision.map(usize::from),
+            };
+            table.write(&mut out, bytes, args.seek)
+        }
+        DumpFormat::Xxd => write_xxd(&mut out, bytes, args.seek, args.view.cols.unwrap_or(16)),
+        DumpFormat::Hexdump => write_hexdump(&mut out, bytes, args.seek),
+    };
+    match result.and_then(|_| out.flush()) {
+        // The reader went away, e.g. piped into `head`
+        Err(err) if err.kind() == ErrorKind::BrokenPipe => Ok(()),
+        result => result,
+    }
+}
+
+impl Table {
+    pub fn header(&self) -> String {
+        let display = match self.display_type {
+            DisplayType::Decimal => "dec",
+            DisplayType::HexaDecimal => "hex",
+            DisplayType::Octal => "oct",
+            DisplayType::Binary => "bin",
+        };
+        let endian = match self.endianness {
+            Endianness::Little => "little",
+            Endianness::Big => "big",
+        };
+        format!(
+            "{HEADER} type={} display={display} endian={endian} cols={}",
+            self.data_type, self.cols
+        )
+    }
+
+    // Same cells as the viewer: true byte offsets, right aligned values, a trailing element cut
+    // short by the end shows its known bytes followed by `??`
+    pub fn write(&self, out: &mut impl Write, bytes: &[u8], start: usize) -> Result<()> {
+        writeln!(out, "{}", self.header())?;
+        let size = self.data_type.size();
+        let width = self.cell_width();
+        for (i, line) in bytes.chunks(self.cols * size).enumerate() {
+            write!(out, "{:08X} ", start + i * self.cols * size)?;
+            for cell in line.chunks(size) {
+                let text = match cell.len() == size {
+                    true => self.format_cell(cell),
+                    false => {
+                        let known: String = cell.iter().map(|b| format!("{b:02X}")).collect();
+                        known + &"??".repeat(size - cell.len())
+                    }
+                };
+                write!(out, " {text:>width$}")?;
+            }
+            let missing = self.cols - line.len().div_ceil(size);
+            write!(out, "{:1$}", "", missing * (width + 1))?;
+            writeln!(out, "  |{}|", printable(line))?;
+        }
+        Ok(())
+    }
+
+    // Apart from decimal, floats show their raw IEEE 754 bits so the dump keeps every bit
+    fn format_cell(&self, cell: &[u8]) -> String {
+        let bits = self.data_type.size() * 8;
+        let raw = self.data_type.decode_bits(cell, &self.endianness);
+        match (self.display_type, self.data_type) {
+            (DisplayType::HexaDecimal, _) => format!("{raw:0digits$X}", digits = bits / 4),
+            (DisplayType::Octal, _) => format!("{raw:0digits$o}", digits = bits.div_ceil(3)),
+            (DisplayType::Binary, _) => group_nibbles(&format!("{raw:0bits$b}")),
+            (DisplayType::Decimal, DataType::F32 | DataType::F64) => {
+                let (precision, width) = (self.precision(), self.cell_width());
+                match self.data_type {
+                    DataType::F32 => {
+                        let value = f32::from_bits(raw as u32);
+                        format_float(value, raw, self.float_notation, precision, width)
+                    }
+                    _ => format_float(
+                        f64::from_bits(raw),
+                        raw,
+                        self.float_notation,
+                        precision,
+                        width,
+                    ),
+                }
+            }
+            (DisplayType::Decimal, _) => self
+                .data_type
+                .decode_int(cell, &self.endianness)
+                .to_string(),
+        }
+    }
+
+    // Inverse of `format_cell`, decimal floats are rounded so they cannot be read back
+    pub fn parse_cell(&self, text: &str) -> std::result::Result<Vec<u8>, String> {
+        let size = self.data_type.size();
+        if let Some(known) = text.find('?') {
+            let bytes = parse_hex_bytes(&text[..known])?;
+            if bytes.len() >= size || !text[known..].chars().all(|c| c == '?') {
+                return Err(format!("invalid partial cell '{text}'"));
+            }
+            return Ok(bytes);
+        }
+        match (self.display_type, self.data_type.int_range()) {
+            (DisplayType::Decimal, Some((min, max))) => {
+                let value = text
+                    .parse::<i128>()
+                    .ok()
+                    .filter(|value| (min..=max).contains(value))
+                    .ok_or_else(|| format!("invalid {} cell '{text}'", self.data_type))?;
+                Ok(self.data_type.encode_int(value, &self.endianness))
+            }
+            (DisplayType::Decimal, None) => Err(String::from(
+                "decimal floats are rounded, dump with --display hex to read them back",
+            )),
+            (display_type, _) => {
+                let radix = match display_type {
+                    DisplayType::Octal => 8,
+                    DisplayType::Binary => 2,
+                    _ => 16,
+                };
+                // Binary cells are grouped by nibble
+                let digits = text.replace('_', "");
+                let bits = Some(digits.as_str())
+                    .filter(|digits| digits.chars().all(|c| c.is_digit(radix)))
+                    .and_then(|digits| u64::from_str_radix(digits, radix).ok())
+                    .filter(|bits| size == 8 || bits >> (size * 8) == 0)
+                    .ok_or_else(|| format!("invalid {} cell '{text}'", self.data_type))?;
+                Ok(self.data_type.encode_bits(bits, &self.endianness))
+            }
+        }
+    }
+
+    fn cell_width(&self) -> usize {
+        match (self.display_type, self.data_type.int_range()) {
+            (DisplayType::HexaDecimal, _) => self.data_type.size() * 2,
+            (DisplayType::Octal, _) => (self.data_type.size() * 8).div_ceil(3),
+            (DisplayType::Binary, _) => self.data_type.size() * 10 - 1,
+            (DisplayType::Decimal, Some((min, max))) => {
+                min.to_string().len().max(max.to_string().len())
+            }
+            (DisplayType::Decimal, None) => {
+                self.float_notation.width(self.data_type, self.precision())
+            }
+        }
+    }
+
+    fn precision(&self) -> usize {
+        self.precision
+            .unwrap_or_else(|| self.data_type.default_precision())
+    }
+}
+
+impl FromStr for Table {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let settings = s
+            .strip_prefix(HEADER)
+            .ok_or_else(|| String::from("missing hexer dump header"))?;
+        let mut table = Table::default();
+        for setting in settings.split_whitespace() {
+            let (key, value) = setting
+                .split_once('=')
+                .ok_or_else(|| format!("invalid setting '{setting}'"))?;
+            match key {
+                "type" => table.data_type = value.parse()?,
+                "display" => table.display_type = value.parse()?,
+                "endian" => table.endianness = value.parse()?,
+                "cols" => {
+                    table.cols = value
+                        .parse()
+                        .map_err(|_| format!("invalid column count '{value}'"))?
+                }
+                _ => return Err(format!("unknown setting '{key}'")),
+            }
+        }
+        Ok(table)
+    }
+}
+
+// `xxd`: lowercase pairs of bytes, the text column lines up on partial lines
+fn write_xxd(out: &mut impl Write, bytes: &[u8], start: usize, cols: u16) -> Result<()> {
+    let cols = cols as usize;
+    let hex_width = cols * 2 + cols.div_ceil(2) - 1;
+    for (i, line) in bytes.chunks(cols).enumerate() {
+        let hex: Vec<String> = line
+            .chunks(2)
+            .map(|pair| pair.iter().map(|b| format!("{b:02x}")).collect())
+            .collect();
+        writeln!(
+            out,
+            "{:08x}: {:hex_width$}  {}",
+            start + i * cols,
+            hex.join(" "),
+            printable(line)
+        )?;
+    }
+    Ok(())
+}
+
+// `hexdump -C`: two groups of eight bytes, runs of identical lines are squeezed into a `*` and
+// the offset past the last byte ends the dump
+fn write_hexdump(out: &mut impl Write, bytes: &[u8], start: usize) -> Result<()> {
+    if bytes.is_empty() {
+        return Ok(());
+    }
+    let mut previous: Option<&[u8]> = None;
+    let mut squeezing = false;
+    for (i, line) in bytes.chunks(16).enumerate() {
+        if previous == Some(line) {
+            if !squeezing {
+                writeln!(out, "*")?;
+                squeezing = true;
+            }
+            continue;
+        }
+        previous = Some(line);
+        squeezing = false;
+        let mut hex = String::new();
+        for (j, b) in line.iter().enumerate() {
+            if j == 8 {
+                hex.push(' ');
+            }
+            hex.push_str(&format!("{b:02x} "));
+        }
+        writeln!(
+            out,
+            "{:08x}  {hex:49} |{}|",
+            start + i * 16,
+            printable(line)
+        )?;
+    }
+    writeln!(out, "{:08x}", start + bytes.len())
+}
+
+fn printable(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| match b {
+            0x20..0x7F => b as char,
+            _ => '.',
+        })
+        .collect()
+}
+
+pub fn parse_hex_bytes(text: &str) -> std::result::Result<Vec<u8>, String> {
+    if !text.len().is_multiple_of(2) {
+        return Err(format!("odd number of hex digits in '{text}'"));
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| {
+            text.get(i..i + 2)
+                .filter(|pair| pair.chars().all(|c| c.is_ascii_hexdigit()))
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| format!("invalid hex byte in '{text}'"))
+        })
+        .collect()
+}
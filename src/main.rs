@@ -1,20 +1,43 @@
-use color_eyre::Result;
+use clap::Parser;
+use cli::{Cli, Command};
+use color_eyre::{Result, eyre::WrapErr};
 use crossterm::event::{self, Event, KeyEvent, KeyEventKind};
 use file_picker::{FilePickerEvent, FilePickerState};
 use ratatui::{DefaultTerminal, Frame};
-use viewer::{ViewerContainer, ViewerContainerEvent};
+use std::{
+    io::{IsTerminal, stdin},
+    path::Path,
+    time::Duration,
+};
+use viewer::{AddressMap, ViewerContainer, ViewerContainerEvent};
 
 #[cfg(debug_assertions)]
 use tracing::{Level, info, instrument};
 #[cfg(debug_assertions)]
 use tracing_appender::non_blocking::WorkerGuard;
 
+mod cli;
+mod dump;
 mod file_picker;
+mod undump;
 mod utils;
 mod viewer;
 
+const TICK_RATE: Duration = Duration::from_millis(50);
+
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
+    let cli = Cli::parse();
+    match cli.command {
+        Some(Command::Dump(args)) => {
+            return dump::dump(&args)
+                .wrap_err_with(|| format!("Could not dump {}", args.file.display()));
+        }
+        Some(Command::Undump(args)) => {
+            return undump::undump(&args).wrap_err("Could not rebuild the file from the dump");
+        }
+        None => {}
+    }
 
     #[cfg(debug_assertions)]
     let _guard = init_tracing()?;
@@ -22,8 +45,24 @@ fn main() -> color_eyre::Result<()> {
     #[cfg(debug_assertions)]
     info!("Starting hexer");
 
+    // The file is opened before taking over the terminal so errors are printed normally. Piped
+    // data is read from stdin while the viewer runs, crossterm then reads the keyboard from /dev/tty.
+    let viewer = match cli.file.as_deref() {
+        Some(file) if file == Path::new("-") => Some(read_stdin()?),
+        None if stdin_has_data() => Some(read_stdin()?),
+        Some(file) => Some(
+            ViewerContainer::default()
+                .with_file(file.to_owned())
+                .wrap_err_with(|| format!("Could not open {}", file.display()))?,
+        ),
+        None => None,
+    };
+    let app = match viewer {
+        Some(viewer) => App::with_viewer(apply_view_args(viewer, &cli)),
+        None => App::new(),
+    };
     let terminal = ratatui::init();
-    let result = App::new().run(terminal);
+    let result = app.run(terminal);
     ratatui::restore();
 
     #[cfg(debug_assertions)]
@@ -31,10 +70,78 @@ fn main() -> color_eyre::Result<()> {
     result
 }
 
+// Pipes and redirected files with content are data. A terminal or an empty redirect such as
+// `</dev/null` from a script opens the file picker.
+fn stdin_has_data() -> bool {
+    let stdin = stdin();
+    if stdin.is_terminal() {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        use std::{
+            fs::File,
+            os::{fd::AsFd, unix::fs::FileTypeExt},
+        };
+        let Ok(metadata) = stdin
+            .as_fd()
+            .try_clone_to_owned()
+            .map(File::from)
+            .and_then(|file| file.metadata())
+        else {
+            return false;
+        };
+        let file_type = metadata.file_type();
+        file_type.is_fifo() || file_type.is_socket() || (file_type.is_file() && metadata.len() > 0)
+    }
+    #[cfg(not(unix))]
+    true
+}
+
+fn read_stdin() -> Result<ViewerContainer> {
+    ViewerContainer::default()
+        .with_stdin()
+        .wrap_err("Could not read stdin")
+}
+
+fn apply_view_args(mut viewer: ViewerContainer, cli: &Cli) -> ViewerContainer {
+    if let Some(data_type) = cli.view.data_type {
+        viewer = viewer.with_data_type(data_type);
+    }
+    if let Some(display) = cli.view.display {
+        viewer = viewer.with_display_type(display);
+    }
+    if let Some(endian) = cli.view.endian {
+        viewer = viewer.with_endianness(endian);
+    }
+    if let Some(float) = cli.view.float {
+        viewer = viewer.with_float_notation(float);
+    }
+    if let Some(precision) = cli.view.precision {
+        viewer = viewer.with_precision(precision as usize);
+    }
+    if let Some(cols) = cli.view.cols {
+        viewer = viewer.with_cols(cols as usize);
+    }
+    if let Some(offset) = cli.offset {
+        viewer = viewer.with_offset(offset);
+    }
+    if let Some(base) = cli.base {
+        viewer = viewer.with_address_map(AddressMap::with_base(base));
+    }
+    if let Some(map) = cli.map.clone() {
+        viewer = viewer.with_address_map(map);
+    }
+    if cli.follow {
+        viewer = viewer.with_follow();
+    }
+    viewer
+}
+
 #[derive(Debug)]
 enum Window {
     FilePicker(FilePickerState),
-    HexViewer(ViewerContainer),
+    HexViewer(Box<ViewerContainer>),
 }
 
 impl Default for Window {
@@ -54,9 +161,19 @@ impl App {
         Self::default()
     }
 
+    pub fn with_viewer(viewer: ViewerContainer) -> Self {
+        Self {
+            window: Window::HexViewer(Box::new(viewer)),
+            ..Self::default()
+        }
+    }
+
     pub fn run(mut self, mut terminal: DefaultTerminal) -> Result<()> {
         self.running = true;
         while self.running {
+            if let Window::HexViewer(ref mut viewer_container) = self.window {
+                viewer_container.tick();
+            }
             terminal.draw(|frame| self.render(frame))?;
             self.handle_crossterm_events()?;
         }
@@ -85,9 +202,14 @@ impl App {
     }
 
     fn handle_crossterm_events(&mut self) -> Result<()> {
+        // Redraw periodically while background work is running, otherwise block on input
+        let busy = matches!(self.window, Window::HexViewer(ref v) if v.is_busy());
+        if busy && !event::poll(TICK_RATE)? {
+            return Ok(());
+        }
         match event::read()? {
             // it's important to check KeyEventKind::Press to avoid handling key release events
-            Event::Key(key) if key.kind == KeyEventKind::Press => self.on_key_event(key),
+            Event::Key(key) if key.kind == KeyEventKind::Press => self.on_key_event(key)?,
             Event::Mouse(_) => {}
             Event::Resize(_, _) => {}
             _ => {}
@@ -96,12 +218,13 @@ impl App {
     }
 
     #[cfg_attr(debug_assertions, instrument(skip_all, name = "App::on_key_event"))]
-    fn on_key_event(&mut self, key: KeyEvent) {
+    fn on_key_event(&mut self, key: KeyEvent) -> Result<()> {
         match self.window {
             Window::FilePicker(ref mut state) => match state.handle_key(key) {
                 FilePickerEvent::Quit => self.quit(),
                 FilePickerEvent::SelectedFile(f) => {
-                    self.window = Window::HexViewer(ViewerContainer::default().with_file(f))
+                    self.window =
+                        Window::HexViewer(Box::new(ViewerContainer::default().with_file(f)?))
                 }
                 FilePickerEvent::Poll => {}
             },
@@ -115,6 +238,7 @@ impl App {
                 ViewerContainerEvent::Poll => {}
             },
         };
+        Ok(())
     }
 
     fn quit(&mut self) {
@@ -0,0 +1,180 @@
+use crate::cli::{DumpFormat, UndumpArgs};
+use crate::dump::{Table, parse_hex_bytes};
+use std::fs::File;
+use std::io::{
+    self, BufRead, BufReader, BufWriter, ErrorKind, IsTerminal, Read, Result, Write, stdin, stdout,
+};
+use std::path::Path;
+
+enum Record {
+    Data(usize, Vec<u8>),
+    // `*`: the previous line repeats up to the next address
+    Repeat,
+}
+
+// Writes the lines in address order. Every line has to start where the previous one ended, gaps
+// are zero filled like `xxd -r` unless strict and lines going backwards are always rejected.
+struct Rebuild<W: Write> {
+    out: W,
+    pos: usize,
+    previous: Vec<u8>,
+    repeat: bool,
+    strict: bool,
+}
+
+pub fn undump(args: &UndumpArgs) -> Result<()> {
+    let input: Box<dyn BufRead> = match args.input.as_deref() {
+        None => Box::new(stdin().lock()),
+        Some(path) if path == Path::new("-") => Box::new(stdin().lock()),
+        Some(path) => Box::new(BufReader::new(File::open(path)?)),
+    };
+    let output: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(File::create(path)?),
+        None if stdout().is_terminal() => {
+            return Err(io::Error::other(
+                "refusing to write binary to a terminal, use --output",
+            ));
+        }
+        None => Box::new(stdout().lock()),
+    };
+    let mut rebuild = Rebuild {
+        out: BufWriter::new(output),
+        pos: 0,
+        previous: Vec::new(),
+        repeat: false,
+        strict: args.strict,
+    };
+
+    let mut format = args.format;
+    let mut table = None;
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+        let format = *format.get_or_insert_with(|| detect(line));
+        let result = match format {
+            DumpFormat::Hexer if line.starts_with('#') && table.is_none() => line
+                .parse::<Table>()
+                .map(|parsed| table = Some(parsed))
+                .map_err(invalid),
+            DumpFormat::Hexer if line.starts_with('#') => Ok(()),
+            _ => parse_line(format, table.as_ref(), line)
+                .map_err(invalid)
+                .and_then(|record| rebuild.push(record)),
+        };
+        result.map_err(|err| match err.kind() {
+            ErrorKind::InvalidData => invalid(format!("line {}: {err}", i + 1)),
+            _ => err,
+        })?;
+    }
+    if rebuild.repeat {
+        return Err(invalid("the dump ends in a repeat without a final address"));
+    }
+    rebuild.out.flush()
+}
+
+fn detect(line: &str) -> DumpFormat {
+    let address = line.split_whitespace().next().unwrap_or_default();
+    if line.starts_with('#') {
+        DumpFormat::Hexer
+    } else if address.ends_with(':') {
+        DumpFormat::Xxd
+    } else {
+        DumpFormat::Hexdump
+    }
+}
+
+fn parse_line(
+    format: DumpFormat,
+    table: Option<&Table>,
+    line: &str,
+) -> std::result::Result<Record, String> {
+    if line.trim() == "*" {
+        return Ok(Record::Repeat);
+    }
+    let (address, rest) = line
+        .split_once(|c: char| c == ':' || c.is_whitespace())
+        .unwrap_or((line, ""));
+    let address = Some(address)
+        .filter(|address| !address.is_empty() && address.chars().all(|c| c.is_ascii_hexdigit()))
+        .and_then(|address| usize::from_str_radix(address, 16).ok())
+        .ok_or_else(|| format!("invalid address '{address}'"))?;
+    // The text column starts after two spaces in xxd and with `|` in the other formats
+    let cells = rest
+        .split_whitespace()
+        .take_while(|cell| !cell.starts_with('|'));
+    let mut bytes = Vec::new();
+    match format {
+        DumpFormat::Xxd => {
+            let hex = rest.trim_start().split("  ").next().unwrap_or_default();
+            bytes = parse_hex_bytes(&hex.replace(' ', ""))?;
+        }
+        DumpFormat::Hexdump => {
+            for cell in cells {
+                if cell.len() != 2 {
+                    return Err(format!("invalid byte '{cell}'"));
+                }
+                bytes.extend(parse_hex_bytes(cell)?);
+            }
+        }
+        DumpFormat::Hexer => {
+            let table = table.ok_or_else(|| String::from("missing hexer dump header"))?;
+            for cell in cells {
+                bytes.extend(table.parse_cell(cell)?);
+            }
+        }
+    }
+    Ok(Record::Data(address, bytes))
+}
+
+impl<W: Write> Rebuild<W> {
+    fn push(&mut self, record: Record) -> Result<()> {
+        let (address, bytes) = match record {
+            Record::Repeat => {
+                self.repeat = true;
+                return Ok(());
+            }
+            Record::Data(address, bytes) => (address, bytes),
+        };
+        if address < self.pos {
+            return Err(invalid(format!(
+                "address 0x{address:X} overlaps the previous line ending at 0x{:X}",
+                self.pos
+            )));
+        }
+        let gap = address - self.pos;
+        if self.repeat {
+            self.repeat = false;
+            let len = self.previous.len();
+            if len == 0 || !gap.is_multiple_of(len) {
+                return Err(invalid(format!(
+                    "the repeated line does not end at address 0x{address:X}"
+                )));
+            }
+            for _ in 0..gap / len {
+                self.out.write_all(&self.previous)?;
+            }
+        } else if gap > 0 {
+            if self.strict {
+                return Err(invalid(format!(
+                    "expected address 0x{:X}, found 0x{address:X}",
+                    self.pos
+                )));
+            }
+            io::copy(&mut io::repeat(0).take(gap as u64), &mut self.out)?;
+        }
+        self.out.write_all(&bytes)?;
+        self.pos = address + bytes.len();
+        // The address line closing a hexdump has no bytes and must not replace the repeated line
+        if !bytes.is_empty() {
+            self.previous = bytes;
+        }
+        Ok(())
+    }
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message.into())
+}
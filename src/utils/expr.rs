@@ -0,0 +1,144 @@
+use std::{iter::Peekable, str::Chars};
+
+// Evaluates an offset expression such as `0x400*3+8`. A leading `+` or `-` makes it relative
+// to `current`.
+pub fn parse_offset(input: &str, current: usize) -> Result<usize, String> {
+    let input = input.trim();
+    let (base, expr) = match input.chars().next() {
+        Some('+') => (current as i128, &input[1..]),
+        Some('-') => (current as i128, input),
+        _ => (0, input),
+    };
+    let offset = base.checked_add(parse_int(expr)?).ok_or("overflow")?;
+    if offset < 0 {
+        return Err(String::from("offset is negative"));
+    }
+    usize::try_from(offset).map_err(|_| String::from("offset is too large"))
+}
+
+pub fn parse_int(input: &str) -> Result<i128, String> {
+    let mut parser = Parser {
+        chars: input.chars().peekable(),
+    };
+    let value = parser.sum()?;
+    parser.skip_spaces();
+    if let Some(c) = parser.chars.next() {
+        return Err(format!("unexpected '{c}'"));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl Parser<'_> {
+    fn skip_spaces(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn sum(&mut self) -> Result<i128, String> {
+        let mut value = self.term()?;
+        loop {
+            self.skip_spaces();
+            match self.chars.peek() {
+                Some('+') => {
+                    self.chars.next();
+                    value = value.checked_add(self.term()?).ok_or("overflow")?;
+                }
+                Some('-') => {
+                    self.chars.next();
+                    value = value.checked_sub(self.term()?).ok_or("overflow")?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<i128, String> {
+        let mut value = self.factor()?;
+        loop {
+            self.skip_spaces();
+            match self.chars.peek() {
+                Some('*') => {
+                    self.chars.next();
+                    value = value.checked_mul(self.factor()?).ok_or("overflow")?;
+                }
+                Some('/') => {
+                    self.chars.next();
+                    value = value
+                        .checked_div(self.factor()?)
+                        .ok_or("division by zero")?;
+                }
+                Some('%') => {
+                    self.chars.next();
+                    value = value
+                        .checked_rem(self.factor()?)
+                        .ok_or("division by zero")?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn factor(&mut self) -> Result<i128, String> {
+        self.skip_spaces();
+        match self.chars.peek() {
+            Some('(') => {
+                self.chars.next();
+                let value = self.sum()?;
+                self.skip_spaces();
+                match self.chars.next() {
+                    Some(')') => Ok(value),
+                    _ => Err(String::from("missing ')'")),
+                }
+            }
+            Some('-') => {
+                self.chars.next();
+                Ok(self.factor()?.checked_neg().ok_or("overflow")?)
+            }
+            Some(c) if c.is_ascii_digit() => self.number(),
+            Some(c) => Err(format!("unexpected '{c}'")),
+            None => Err(String::from("expected a number")),
+        }
+    }
+
+    fn number(&mut self) -> Result<i128, String> {
+        let mut digits = String::new();
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_ascii_alphanumeric() || *c == '_')
+        {
+            if c != '_' {
+                digits.push(c);
+            }
+        }
+        let (radix, digits) = match digits.get(..2) {
+            Some("0x" | "0X") => (16, &digits[2..]),
+            Some("0o" | "0O") => (8, &digits[2..]),
+            Some("0b" | "0B") => (2, &digits[2..]),
+            _ => (10, &digits[..]),
+        };
+        i128::from_str_radix(digits, radix).map_err(|_| format!("invalid number '{digits}'"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overflow_is_an_error() {
+        let max = "0x7fffffffffffffffffffffffffffffff";
+        assert_eq!(
+            parse_offset(&format!("+{max}"), 1),
+            Err(String::from("overflow"))
+        );
+        assert_eq!(
+            parse_int(&format!("-(-{max}-1)")),
+            Err(String::from("overflow"))
+        );
+        assert_eq!(parse_offset("+0x10*2", 8), Ok(40));
+        assert_eq!(parse_offset("-8", 8), Ok(0));
+    }
+}
@@ -1,4 +1,13 @@
-use std::path::{Component, Path, PathBuf};
+use std::{
+    fs::{File, OpenOptions},
+    io::{ErrorKind, Result},
+    path::{Component, Path, PathBuf},
+    process,
+};
+
+mod expr;
+
+pub use expr::{parse_int, parse_offset};
 
 pub fn last_n_components(path: &Path, n: usize) -> (usize, PathBuf) {
     let comps: Vec<Component> = path.components().rev().take(n).collect();
@@ -12,3 +21,22 @@ pub fn previous_power_of_two(x: u16) -> u16 {
         1 << (15 - x.leading_zeros())
     }
 }
+
+// A new file in `dir` named `{stem}-{pid}-{n}.{ext}`. `create_new` fails instead of following a
+// link planted under the same name in a shared directory such as the temp directory.
+pub fn create_temp_file(dir: &Path, stem: &str, ext: &str) -> Result<(PathBuf, File)> {
+    let mut n = 0;
+    loop {
+        let path = dir.join(format!("{stem}-{}-{n}.{ext}", process::id()));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path);
+        match file {
+            Ok(file) => return Ok((path, file)),
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => n += 1,
+            Err(err) => return Err(err),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::utils::parse_int;
+use std::str::FromStr;
+
+// A run of the file loaded at `address`. Without a length it runs to the end of the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Segment {
+    offset: usize,
+    len: Option<usize>,
+    address: usize,
+}
+
+// Maps file offsets to the addresses the data is loaded at, e.g. a flash dump at 0x08000000.
+// Without segments addresses are plain file offsets.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AddressMap {
+    segments: Vec<Segment>,
+}
+
+impl Segment {
+    fn end(&self) -> usize {
+        match self.len {
+            Some(len) => self.offset.saturating_add(len),
+            None => usize::MAX,
+        }
+    }
+}
+
+impl AddressMap {
+    pub fn with_base(base: usize) -> Self {
+        Self {
+            segments: vec![Segment {
+                offset: 0,
+                len: None,
+                address: base,
+            }],
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    // None for offsets outside of every segment
+    pub fn to_address(&self, offset: usize) -> Option<usize> {
+        if self.is_empty() {
+            return Some(offset);
+        }
+        self.segments
+            .iter()
+            .find(|segment| (segment.offset..segment.end()).contains(&offset))
+            .and_then(|segment| segment.address.checked_add(offset - segment.offset))
+    }
+
+    pub fn to_offset(&self, address: usize) -> Option<usize> {
+        if self.is_empty() {
+            return Some(address);
+        }
+        self.segments.iter().find_map(|segment| {
+            let delta = address.checked_sub(segment.address)?;
+            let offset = segment.offset.checked_add(delta)?;
+            (offset < segment.end()).then_some(offset)
+        })
+    }
+
+    // Highest address of a file of `len` bytes, sizes the address column
+    pub fn end_address(&self, len: usize) -> usize {
+        if self.is_empty() {
+            return len;
+        }
+        self.segments
+            .iter()
+            .filter(|segment| segment.offset < len)
+            .map(|segment| {
+                let mapped = segment.end().min(len) - segment.offset;
+                segment.address.saturating_add(mapped)
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+// Comma separated `OFFSET:LEN@ADDRESS` segments, `OFFSET@ADDRESS` maps up to the end of the file.
+// Segments may not overlap in the file, nor in memory when both have a length.
+impl FromStr for AddressMap {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut segments: Vec<Segment> = Vec::new();
+        for spec in s.split(',') {
+            let (range, address) = spec
+                .split_once('@')
+                .ok_or_else(|| format!("expected OFFSET:LEN@ADDRESS, found '{}'", spec.trim()))?;
+            let (offset, len) = match range.split_once(':') {
+                Some((offset, len)) => (offset, Some(parse_usize(len)?)),
+                None => (range, None),
+            };
+            let segment = Segment {
+                offset: parse_usize(offset)?,
+                len,
+                address: parse_usize(address)?,
+            };
+            if segment.len == Some(0) {
+                return Err(format!("empty segment '{}'", spec.trim()));
+            }
+            // Open ended segments stop at the end of the file, which is not known yet
+            let address_end = |s: &Segment| s.len.map(|len| s.address.saturating_add(len));
+            let overlaps = segments.iter().any(|other| {
+                let in_file = segment.offset < other.end() && other.offset < segment.end();
+                let in_memory = match (address_end(&segment), address_end(other)) {
+                    (Some(end), Some(other_end)) => {
+                        segment.address < other_end && other.address < end
+                    }
+                    _ => false,
+                };
+                in_file || in_memory
+            });
+            if overlaps {
+                return Err(format!("segment '{}' overlaps another one", spec.trim()));
+            }
+            segments.push(segment);
+        }
+        Ok(Self { segments })
+    }
+}
+
+fn parse_usize(input: &str) -> Result<usize, String> {
+    usize::try_from(parse_int(input)?).map_err(|_| format!("'{}' is out of range", input.trim()))
+}
@@ -0,0 +1,110 @@
+use crate::utils::create_temp_file;
+use memmap2::Mmap;
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, Read, Result, stdin},
+    path::Path,
+    sync::Arc,
+    thread::{self, JoinHandle},
+};
+
+#[derive(Debug, Default, Clone)]
+pub enum ByteSource {
+    #[default]
+    Empty,
+    Mapped(Arc<Mmap>),
+    // Data that cannot be mapped, e.g. read from a pipe
+    Owned(Arc<Vec<u8>>),
+}
+
+impl ByteSource {
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::map(&File::open(path)?)
+    }
+
+    pub fn map(file: &File) -> Result<Self> {
+        // Empty files cannot be mapped on every platform
+        if file.metadata()?.len() == 0 {
+            return Ok(ByteSource::Empty);
+        }
+        // SAFETY: the file is only read through the map. If another process truncates it while
+        // mapped the OS may deliver SIGBUS, which is accepted for a viewer (same as less/hexyl).
+        let map = unsafe { Mmap::map(file)? };
+        Ok(ByteSource::Mapped(Arc::new(map)))
+    }
+
+    // Reads until the end of stdin, the viewer uses `StdinStream` to show data as it arrives
+    pub fn stdin() -> Result<Self> {
+        let mut bytes = Vec::new();
+        stdin().lock().read_to_end(&mut bytes)?;
+        Ok(ByteSource::Owned(Arc::new(bytes)))
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            ByteSource::Empty => 0,
+            ByteSource::Mapped(map) => map.len(),
+            ByteSource::Owned(bytes) => bytes.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns at most `len` bytes starting at `offset`, clamped to the end of the source.
+    pub fn read(&self, offset: usize, len: usize) -> &[u8] {
+        let bytes: &[u8] = match self {
+            ByteSource::Empty => &[],
+            ByteSource::Mapped(map) => map,
+            ByteSource::Owned(bytes) => bytes,
+        };
+        let start = offset.min(bytes.len());
+        let end = offset.saturating_add(len).min(bytes.len());
+        &bytes[start..end]
+    }
+}
+
+// Stdin copied into a temp file by a thread as it arrives. Mapping the file shows what came in so
+// far, so a producer that never exits can still be watched.
+#[derive(Debug)]
+pub struct StdinStream {
+    file: File,
+    reader: Option<JoinHandle<Result<u64>>>,
+}
+
+impl StdinStream {
+    pub fn spawn() -> Result<Self> {
+        let (path, file) = create_temp_file(&env::temp_dir(), "hexer-stdin", "bin")?;
+        // The open handles keep the data alive, nothing is left behind on exit
+        let _ = fs::remove_file(path);
+        let mut writer = file.try_clone()?;
+        let reader = thread::spawn(move || io::copy(&mut stdin().lock(), &mut writer));
+        Ok(Self {
+            file,
+            reader: Some(reader),
+        })
+    }
+
+    pub fn len(&self) -> Result<usize> {
+        Ok(self.file.metadata()?.len() as usize)
+    }
+
+    pub fn source(&self) -> Result<ByteSource> {
+        ByteSource::map(&self.file)
+    }
+
+    // The outcome of the reader once stdin is closed or failed, None while it is still reading
+    pub fn finished(&mut self) -> Option<Result<u64>> {
+        if !self.reader.as_ref()?.is_finished() {
+            return None;
+        }
+        let reader = self.reader.take()?;
+        Some(
+            reader
+                .join()
+                .unwrap_or_else(|_| Err(io::Error::other("the stdin reader panicked"))),
+        )
+    }
+}
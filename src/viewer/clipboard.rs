@@ -0,0 +1,129 @@
+use crate::utils::create_temp_file;
+use std::{
+    env,
+    io::{Result, Write, stdout},
+    path::PathBuf,
+};
+
+// Terminals cap the size of an OSC 52 sequence, larger copies go to a file instead
+const OSC52_LIMIT: usize = 1 << 20;
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyFormat {
+    Hex,
+    CArray,
+    RustArray,
+    Base64,
+    Raw,
+}
+
+pub enum Copied {
+    Clipboard,
+    File(PathBuf),
+}
+
+impl CopyFormat {
+    pub const ALL: [CopyFormat; 5] = [
+        CopyFormat::Hex,
+        CopyFormat::CArray,
+        CopyFormat::RustArray,
+        CopyFormat::Base64,
+        CopyFormat::Raw,
+    ];
+
+    pub fn key(&self) -> char {
+        match self {
+            CopyFormat::Hex => 'x',
+            CopyFormat::CArray => 'c',
+            CopyFormat::RustArray => 'r',
+            CopyFormat::Base64 => 'b',
+            CopyFormat::Raw => 'w',
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CopyFormat::Hex => "Hex string",
+            CopyFormat::CArray => "C array",
+            CopyFormat::RustArray => "Rust [u8; N]",
+            CopyFormat::Base64 => "Base64",
+            CopyFormat::Raw => "Raw bytes",
+        }
+    }
+
+    pub fn encode(&self, bytes: &[u8]) -> Vec<u8> {
+        match self {
+            CopyFormat::Hex => bytes.iter().map(|b| format!("{b:02x}")).collect::<String>(),
+            CopyFormat::CArray => format!(
+                "unsigned char data[{}] = {{\n{}}};\n",
+                bytes.len(),
+                array_body(bytes)
+            ),
+            CopyFormat::RustArray => format!(
+                "const DATA: [u8; {}] = [\n{}];\n",
+                bytes.len(),
+                array_body(bytes)
+            ),
+            CopyFormat::Base64 => base64(bytes),
+            CopyFormat::Raw => return bytes.to_vec(),
+        }
+        .into_bytes()
+    }
+}
+
+// Twelve bytes per line, like `xxd -i`
+fn array_body(bytes: &[u8]) -> String {
+    let mut body = String::new();
+    for line in bytes.chunks(12) {
+        let line: Vec<String> = line.iter().map(|b| format!("0x{b:02x}")).collect();
+        body.push_str("    ");
+        body.push_str(&line.join(", "));
+        body.push_str(",\n");
+    }
+    body
+}
+
+fn base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | ((b as u32) << (16 - 8 * i)));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_CHARS[((n >> (18 - 6 * i)) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+// Sets the system clipboard through the terminal with OSC 52, which also works over SSH. Payloads
+// that are too large or not text are written to a file in the temp directory instead.
+pub fn copy(format: CopyFormat, bytes: &[u8]) -> Result<Copied> {
+    let payload = format.encode(bytes);
+    if payload.len() > OSC52_LIMIT || std::str::from_utf8(&payload).is_err() {
+        let ext = match format {
+            CopyFormat::Raw => "bin",
+            _ => "txt",
+        };
+        // A new file for every copy
+        let (path, mut file) = create_temp_file(&env::temp_dir(), "hexer-selection", ext)?;
+        file.write_all(&payload)?;
+        return Ok(Copied::File(path));
+    }
+
+    let mut sequence = format!("\x1b]52;c;{}\x07", base64(&payload));
+    // tmux only forwards the sequence to the outer terminal when wrapped in a passthrough
+    if env::var_os("TMUX").is_some() {
+        sequence = format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"));
+    }
+    let mut out = stdout();
+    out.write_all(sequence.as_bytes())?;
+    out.flush()?;
+    Ok(Copied::Clipboard)
+}
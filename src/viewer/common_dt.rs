@@ -1,6 +1,6 @@
-use std::fmt;
+use std::{fmt, str::FromStr};
 
-#[derive(Debug, Default, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum DataType {
     #[default]
     U8,
@@ -15,13 +15,27 @@ pub enum DataType {
     F64,
 }
 
-#[derive(Debug, Default, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum DisplayType {
     #[default]
     Decimal,
     HexaDecimal,
+    Octal,
+    Binary,
 }
 
+// How decimal display writes float values, like `%e`, `%f` and `%a` of printf
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FloatNotation {
+    #[default]
+    Scientific,
+    Fixed,
+    HexFloat,
+}
+
+// More digits than any f64 needs to round trip
+pub const MAX_PRECISION: usize = 17;
+
 impl DataType {
     pub const ALL: [DataType; 10] = [
         DataType::U8,
@@ -35,14 +49,134 @@ impl DataType {
         DataType::F32,
         DataType::F64,
     ];
+
+    pub fn size(&self) -> usize {
+        match self {
+            DataType::U8 | DataType::I8 => 1,
+            DataType::U16 | DataType::I16 => 2,
+            DataType::U32 | DataType::I32 | DataType::F32 => 4,
+            DataType::U64 | DataType::I64 | DataType::F64 => 8,
+        }
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, DataType::F32 | DataType::F64)
+    }
+
+    // Digits after the point of float values unless a precision is set
+    pub fn default_precision(&self) -> usize {
+        match self {
+            DataType::F32 => 5,
+            _ => 10,
+        }
+    }
+
+    pub fn int_range(&self) -> Option<(i128, i128)> {
+        match self {
+            DataType::U8 => Some((0, u8::MAX as i128)),
+            DataType::I8 => Some((i8::MIN as i128, i8::MAX as i128)),
+            DataType::U16 => Some((0, u16::MAX as i128)),
+            DataType::I16 => Some((i16::MIN as i128, i16::MAX as i128)),
+            DataType::U32 => Some((0, u32::MAX as i128)),
+            DataType::I32 => Some((i32::MIN as i128, i32::MAX as i128)),
+            DataType::U64 => Some((0, u64::MAX as i128)),
+            DataType::I64 => Some((i64::MIN as i128, i64::MAX as i128)),
+            DataType::F32 | DataType::F64 => None,
+        }
+    }
+
+    // Integer types widen to i128, floats to f64, so values of any type can be compared
+    pub fn decode_int(&self, bytes: &[u8], endianness: &Endianness) -> i128 {
+        match self {
+            DataType::U8 => u8::from_bytes(bytes, endianness) as i128,
+            DataType::I8 => i8::from_bytes(bytes, endianness) as i128,
+            DataType::U16 => u16::from_bytes(bytes, endianness) as i128,
+            DataType::I16 => i16::from_bytes(bytes, endianness) as i128,
+            DataType::U32 => u32::from_bytes(bytes, endianness) as i128,
+            DataType::I32 => i32::from_bytes(bytes, endianness) as i128,
+            DataType::U64 => u64::from_bytes(bytes, endianness) as i128,
+            DataType::I64 => i64::from_bytes(bytes, endianness) as i128,
+            DataType::F32 | DataType::F64 => self.decode_float(bytes, endianness) as i128,
+        }
+    }
+
+    pub fn decode_float(&self, bytes: &[u8], endianness: &Endianness) -> f64 {
+        match self {
+            DataType::F32 => f32::from_bytes(bytes, endianness) as f64,
+            DataType::F64 => f64::from_bytes(bytes, endianness),
+            _ => self.decode_int(bytes, endianness) as f64,
+        }
+    }
+
+    // Raw bits of the value, most significant first, regardless of signedness or float-ness
+    pub fn decode_bits(&self, bytes: &[u8], endianness: &Endianness) -> u64 {
+        let bytes = &bytes[..self.size()];
+        let fold = |bits: u64, &b: &u8| (bits << 8) | b as u64;
+        match endianness {
+            Endianness::Little => bytes.iter().rev().fold(0, fold),
+            Endianness::Big => bytes.iter().fold(0, fold),
+        }
+    }
+
+    pub fn encode_bits(&self, bits: u64, endianness: &Endianness) -> Vec<u8> {
+        let mut bytes = bits.to_le_bytes()[..self.size()].to_vec();
+        if let Endianness::Big = endianness {
+            bytes.reverse();
+        }
+        bytes
+    }
+
+    // Integers are truncated to the type, callers check `int_range` first
+    pub fn encode_int(&self, value: i128, endianness: &Endianness) -> Vec<u8> {
+        self.encode_bits(value as u64, endianness)
+    }
+
+    pub fn encode_float(&self, value: f64, endianness: &Endianness) -> Vec<u8> {
+        match self {
+            DataType::F32 => self.encode_bits((value as f32).to_bits() as u64, endianness),
+            DataType::F64 => self.encode_bits(value.to_bits(), endianness),
+            _ => self.encode_int(value as i128, endianness),
+        }
+    }
 }
-#[derive(Debug, Default, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum Endianness {
     #[default]
     Little,
     Big,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharSet {
+    Ascii,
+    Latin1,
+    Utf8,
+}
+
+pub trait FromBytes: Sized {
+    const SIZE: usize;
+    fn from_bytes(bytes: &[u8], endianness: &Endianness) -> Self;
+}
+
+macro_rules! impl_from_bytes {
+    ($($t:ty),*) => {
+        $(
+            impl FromBytes for $t {
+                const SIZE: usize = size_of::<$t>();
+                fn from_bytes(bytes: &[u8], endianness: &Endianness) -> Self {
+                    let raw = bytes[..Self::SIZE].try_into().unwrap();
+                    match endianness {
+                        Endianness::Little => <$t>::from_le_bytes(raw),
+                        Endianness::Big => <$t>::from_be_bytes(raw),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_bytes!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
 impl fmt::Display for DataType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -59,3 +193,140 @@ impl fmt::Display for DataType {
         }
     }
 }
+
+impl fmt::Display for CharSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CharSet::Ascii => write!(f, "ASCII"),
+            CharSet::Latin1 => write!(f, "Latin-1"),
+            CharSet::Utf8 => write!(f, "UTF-8"),
+        }
+    }
+}
+
+impl FromStr for DataType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DataType::ALL
+            .into_iter()
+            .find(|data_type| data_type.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown data type '{s}'"))
+    }
+}
+
+impl FromStr for DisplayType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "dec" | "decimal" => Ok(DisplayType::Decimal),
+            "hex" | "hexadecimal" => Ok(DisplayType::HexaDecimal),
+            "oct" | "octal" => Ok(DisplayType::Octal),
+            "bin" | "binary" => Ok(DisplayType::Binary),
+            _ => Err(format!("unknown display type '{s}'")),
+        }
+    }
+}
+
+impl FloatNotation {
+    // Characters of a float cell, fixed notation gets the room of the scientific one
+    pub fn width(&self, data_type: DataType, precision: usize) -> usize {
+        match (self, data_type) {
+            (FloatNotation::HexFloat, DataType::F32) => 16,
+            (FloatNotation::HexFloat, _) => 24,
+            (_, DataType::F32) => precision + 9,
+            _ => precision + 13,
+        }
+    }
+}
+
+impl FromStr for FloatNotation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "sci" | "scientific" => Ok(FloatNotation::Scientific),
+            "fixed" => Ok(FloatNotation::Fixed),
+            "hexfloat" => Ok(FloatNotation::HexFloat),
+            _ => Err(format!("unknown float notation '{s}'")),
+        }
+    }
+}
+
+impl FromStr for Endianness {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "le" | "little" => Ok(Endianness::Little),
+            "be" | "big" => Ok(Endianness::Big),
+            _ => Err(format!("unknown endianness '{s}'")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BYTES: [u8; 8] = [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF];
+
+    #[test]
+    fn from_bytes_big_endian() {
+        let big = &Endianness::Big;
+        assert_eq!(u16::from_bytes(&BYTES, big), 0x0123);
+        assert_eq!(i16::from_bytes(&BYTES[4..], big), 0x89ABu16 as i16);
+        assert_eq!(u32::from_bytes(&BYTES, big), 0x0123_4567);
+        assert_eq!(i32::from_bytes(&BYTES[4..], big), 0x89AB_CDEFu32 as i32);
+        assert_eq!(u64::from_bytes(&BYTES, big), 0x0123_4567_89AB_CDEF);
+        assert_eq!(i64::from_bytes(&BYTES, big), 0x0123_4567_89AB_CDEF);
+        assert_eq!(f32::from_bytes(&[0x41, 0x40, 0x00, 0x00], big), 12.0);
+        assert_eq!(f64::from_bytes(&[0xC0, 0x28, 0, 0, 0, 0, 0, 0], big), -12.0);
+    }
+
+    #[test]
+    fn from_bytes_little_endian() {
+        let little = &Endianness::Little;
+        assert_eq!(u16::from_bytes(&BYTES, little), 0x2301);
+        assert_eq!(i16::from_bytes(&BYTES[4..], little), 0xAB89u16 as i16);
+        assert_eq!(u32::from_bytes(&BYTES, little), 0x6745_2301);
+        assert_eq!(i32::from_bytes(&BYTES[4..], little), 0xEFCD_AB89u32 as i32);
+        assert_eq!(u64::from_bytes(&BYTES, little), 0xEFCD_AB89_6745_2301);
+        assert_eq!(
+            i64::from_bytes(&BYTES, little),
+            0xEFCD_AB89_6745_2301u64 as i64
+        );
+        assert_eq!(f32::from_bytes(&[0x00, 0x00, 0x40, 0x41], little), 12.0);
+        assert_eq!(
+            f64::from_bytes(&[0, 0, 0, 0, 0, 0, 0x28, 0xC0], little),
+            -12.0
+        );
+    }
+
+    #[test]
+    fn decode_bits_follows_byte_order() {
+        for data_type in DataType::ALL {
+            let size = data_type.size();
+            let little = data_type.decode_bits(&BYTES, &Endianness::Little);
+            let big = data_type.decode_bits(&BYTES, &Endianness::Big);
+            assert_eq!(little.to_le_bytes()[..size], BYTES[..size], "{data_type}");
+            assert_eq!(big.to_be_bytes()[8 - size..], BYTES[..size], "{data_type}");
+        }
+    }
+
+    #[test]
+    fn encode_bits_round_trips() {
+        for data_type in DataType::ALL {
+            for endianness in [Endianness::Little, Endianness::Big] {
+                let bits = data_type.decode_bits(&BYTES, &endianness);
+                let bytes = data_type.encode_bits(bits, &endianness);
+                assert_eq!(
+                    bytes,
+                    BYTES[..data_type.size()],
+                    "{data_type} {endianness:?}"
+                );
+            }
+        }
+    }
+}
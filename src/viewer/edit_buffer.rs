@@ -0,0 +1,555 @@
+use super::byte_source::ByteSource;
+use crate::utils::create_temp_file;
+use std::{
+    borrow::Cow,
+    fs::{self, File},
+    io::{BufWriter, Result, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Origin {
+    File,
+    Added,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Piece {
+    origin: Origin,
+    start: usize,
+    len: usize,
+}
+
+// Everything needed to return to an earlier state, pieces never change once in the table
+#[derive(Debug, Clone)]
+struct Snapshot {
+    source: ByteSource,
+    source_id: u64,
+    pieces: Vec<Piece>,
+    len: usize,
+    id: u64,
+    cursor: usize,
+}
+
+// Piece table over the mapped file: the content is the concatenation of the pieces, each one a
+// run of either the original file or the append-only `added` bytes. Edits only split and
+// reorder pieces, so the file itself is never copied into memory.
+#[derive(Debug, Default, Clone)]
+pub struct Contents {
+    source: ByteSource,
+    // Shared with searches running on the content, an edit during one copies it
+    added: Arc<Vec<u8>>,
+    pieces: Vec<Piece>,
+    len: usize,
+}
+
+// The contents with their undo history
+#[derive(Debug, Default)]
+pub struct EditBuffer {
+    contents: Contents,
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    // Cursor of the edit started by `begin`, the undo step is recorded on its first change
+    pending: Option<usize>,
+    id: u64,
+    next_id: u64,
+    saved_id: u64,
+    // Changes when a save replaces the source, a source that only grew keeps its id
+    source_id: u64,
+}
+
+impl EditBuffer {
+    pub fn new(source: ByteSource) -> Self {
+        let len = source.len();
+        let pieces = match len {
+            0 => Vec::new(),
+            _ => vec![Piece {
+                origin: Origin::File,
+                start: 0,
+                len,
+            }],
+        };
+        Self {
+            contents: Contents {
+                source,
+                added: Arc::default(),
+                pieces,
+                len,
+            },
+            undo: Vec::new(),
+            redo: Vec::new(),
+            pending: None,
+            id: 0,
+            next_id: 1,
+            saved_id: 0,
+            source_id: 0,
+        }
+    }
+
+    // A copy of the current content that does not change with later edits
+    pub fn contents(&self) -> Contents {
+        self.contents.clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.contents.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contents.len == 0
+    }
+
+    // Length of the file behind the buffer, edits aside
+    pub fn source_len(&self) -> usize {
+        self.contents.source.len()
+    }
+
+    // Takes over a source that grew at the end, e.g. a capture still being written. The new bytes
+    // follow the current content and are not an edit, so nothing is recorded for undo.
+    pub fn append_source(&mut self, source: ByteSource) {
+        let (start, end) = (self.contents.source.len(), source.len());
+        if end <= start {
+            return;
+        }
+        self.contents.source = source;
+        // A source growing every tick extends its last piece instead of piling up new ones
+        match self.contents.pieces.last_mut() {
+            Some(last) if last.origin == Origin::File && last.start + last.len == start => {
+                last.len += end - start
+            }
+            _ => self.contents.pieces.push(Piece {
+                origin: Origin::File,
+                start,
+                len: end - start,
+            }),
+        }
+        self.contents.len += end - start;
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.id != self.saved_id
+    }
+
+    // Starts a new undo step, every change until the next call is undone at once
+    pub fn begin(&mut self, cursor: usize) {
+        self.pending = Some(cursor);
+    }
+
+    // Returns the cursor from before the undone edit
+    pub fn undo(&mut self) -> Option<usize> {
+        let snapshot = self.undo.pop()?;
+        let cursor = snapshot.cursor;
+        let current = self.restore(snapshot);
+        self.redo.push(current);
+        Some(cursor)
+    }
+
+    pub fn redo(&mut self) -> Option<usize> {
+        let snapshot = self.redo.pop()?;
+        let current = self.restore(snapshot);
+        let cursor = current.cursor;
+        self.undo.push(current);
+        Some(cursor)
+    }
+
+    fn snapshot(&self, cursor: usize) -> Snapshot {
+        Snapshot {
+            source: self.contents.source.clone(),
+            source_id: self.source_id,
+            pieces: self.contents.pieces.clone(),
+            len: self.contents.len,
+            id: self.id,
+            cursor,
+        }
+    }
+
+    // Swaps in the snapshot and returns the replaced state, which keeps the snapshot's cursor.
+    // Bytes the source gained since the snapshot are not part of any edit and stay at the end.
+    fn restore(&mut self, snapshot: Snapshot) -> Snapshot {
+        let current = self.snapshot(snapshot.cursor);
+        self.contents.source = snapshot.source;
+        self.contents.pieces = snapshot.pieces;
+        self.contents.len = snapshot.len;
+        self.id = snapshot.id;
+        self.source_id = snapshot.source_id;
+        if self.source_id == current.source_id {
+            self.append_source(current.source.clone());
+        }
+        self.pending = None;
+        current
+    }
+
+    // Called before every change, the state gets a new id so it is dirty until saved
+    fn record(&mut self) {
+        if let Some(cursor) = self.pending.take() {
+            self.undo.push(self.snapshot(cursor));
+        }
+        self.redo.clear();
+        self.id = self.next_id;
+        self.next_id += 1;
+    }
+
+    // Whether any byte in the range was typed rather than read from the file
+    pub fn is_modified(&self, offset: usize, len: usize) -> bool {
+        self.contents
+            .pieces_in(offset, len)
+            .any(|piece| piece.origin == Origin::Added)
+    }
+
+    pub fn read(&self, offset: usize, len: usize) -> Cow<'_, [u8]> {
+        self.contents.read(offset, len)
+    }
+
+    // Bytes past the end are dropped, overwriting never changes the length
+    pub fn overwrite(&mut self, offset: usize, bytes: &[u8]) {
+        let len = bytes.len().min(self.contents.len.saturating_sub(offset));
+        if len == 0 {
+            return;
+        }
+        self.delete(offset, len);
+        self.insert(offset, &bytes[..len]);
+    }
+
+    pub fn insert(&mut self, offset: usize, bytes: &[u8]) {
+        if bytes.is_empty() || offset > self.contents.len {
+            return;
+        }
+        self.record();
+        let at = self.contents.split(offset);
+        let piece = Piece {
+            origin: Origin::Added,
+            start: self.contents.added.len(),
+            len: bytes.len(),
+        };
+        Arc::make_mut(&mut self.contents.added).extend_from_slice(bytes);
+        // Typing byte after byte keeps extending the same piece
+        match at.checked_sub(1).map(|i| &mut self.contents.pieces[i]) {
+            Some(prev) if prev.origin == Origin::Added && prev.start + prev.len == piece.start => {
+                prev.len += piece.len
+            }
+            _ => self.contents.pieces.insert(at, piece),
+        }
+        self.contents.len += bytes.len();
+    }
+
+    pub fn delete(&mut self, offset: usize, len: usize) {
+        let len = len.min(self.contents.len.saturating_sub(offset));
+        if len == 0 {
+            return;
+        }
+        self.record();
+        let start = self.contents.split(offset);
+        let end = self.contents.split(offset + len);
+        self.contents.pieces.drain(start..end);
+        self.contents.len -= len;
+    }
+
+    // Writes everything to a temporary file next to `path` and renames it over the original, so
+    // a failed save never leaves a half-written file behind. The buffer then maps the new file,
+    // the history keeps the old mapping alive for undoing past the save.
+    pub fn save(&mut self, path: &Path) -> Result<()> {
+        let path = self.save_keeping_source(path)?;
+        let saved = Self::new(ByteSource::open(&path)?);
+        self.contents.source = saved.contents.source;
+        self.contents.pieces = saved.contents.pieces;
+        self.source_id += 1;
+        Ok(())
+    }
+
+    // Saves without mapping the new file, for a source that is still growing. Symlinks are
+    // followed so the target is replaced rather than the link. Only the permissions carry over:
+    // the new file breaks hard links and gets the owner and xattrs of a new file.
+    pub fn save_keeping_source(&mut self, path: &Path) -> Result<PathBuf> {
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let (tmp, file) = create_temp_file(dir, &format!(".{name}"), "hexer-tmp")?;
+        let result = self.write_to(file).and_then(|_| {
+            // A new file, e.g. data from stdin saved under a name, keeps the default permissions
+            if let Ok(metadata) = fs::metadata(&path) {
+                fs::set_permissions(&tmp, metadata.permissions())?;
+            }
+            fs::rename(&tmp, &path)
+        });
+        if result.is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+        result?;
+        self.saved_id = self.id;
+        Ok(path)
+    }
+
+    fn write_to(&self, file: File) -> Result<()> {
+        let mut out = BufWriter::new(file);
+        for piece in &self.contents.pieces {
+            out.write_all(&self.contents.bytes(*piece))?;
+        }
+        out.into_inner()?.sync_all()
+    }
+}
+
+impl Contents {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    // Same as `ByteSource::read`, only copies when the range spans edited pieces
+    pub fn read(&self, offset: usize, len: usize) -> Cow<'_, [u8]> {
+        let mut pieces = self.pieces_in(offset, len);
+        let Some(first) = pieces.next() else {
+            return Cow::Borrowed(&[]);
+        };
+        let first_bytes = self.bytes(first);
+        let Some(second) = pieces.next() else {
+            return first_bytes;
+        };
+        let mut bytes = first_bytes.into_owned();
+        bytes.extend_from_slice(&self.bytes(second));
+        for piece in pieces {
+            bytes.extend_from_slice(&self.bytes(piece));
+        }
+        Cow::Owned(bytes)
+    }
+
+    fn bytes(&self, piece: Piece) -> Cow<'_, [u8]> {
+        match piece.origin {
+            Origin::File => Cow::Borrowed(self.source.read(piece.start, piece.len)),
+            Origin::Added => Cow::Borrowed(&self.added[piece.start..piece.start + piece.len]),
+        }
+    }
+
+    // The parts of the pieces covering `offset..offset + len`, trimmed to the range
+    fn pieces_in(&self, offset: usize, len: usize) -> impl Iterator<Item = Piece> + '_ {
+        let end = offset.saturating_add(len);
+        let mut piece_start = 0;
+        self.pieces.iter().filter_map(move |piece| {
+            let (start, piece_end) = (piece_start, piece_start + piece.len);
+            piece_start = piece_end;
+            if piece_end <= offset || start >= end {
+                return None;
+            }
+            let skip = offset.saturating_sub(start);
+            Some(Piece {
+                origin: piece.origin,
+                start: piece.start + skip,
+                len: piece_end.min(end) - start - skip,
+            })
+        })
+    }
+
+    // Makes `offset` fall on a piece boundary and returns the index of the piece starting there
+    fn split(&mut self, offset: usize) -> usize {
+        let mut start = 0;
+        for i in 0..self.pieces.len() {
+            let piece = self.pieces[i];
+            if offset == start {
+                return i;
+            }
+            if offset < start + piece.len {
+                let head = offset - start;
+                self.pieces[i].len = head;
+                let tail = Piece {
+                    start: piece.start + head,
+                    len: piece.len - head,
+                    ..piece
+                };
+                self.pieces.insert(i + 1, tail);
+                return i + 1;
+            }
+            start += piece.len;
+        }
+        self.pieces.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A file of its own in the temp directory, removed with the guard
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str, bytes: &[u8]) -> Self {
+            let path = std::env::temp_dir().join(format!("hexer-{}-{name}", std::process::id()));
+            fs::write(&path, bytes).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn buffer(bytes: &[u8]) -> EditBuffer {
+        EditBuffer::new(ByteSource::Owned(Arc::new(bytes.to_vec())))
+    }
+
+    fn content(buffer: &EditBuffer) -> Vec<u8> {
+        buffer.read(0, buffer.len()).into_owned()
+    }
+
+    #[test]
+    fn insert_at_start_middle_and_end() {
+        let mut buffer = buffer(b"abcdef");
+        buffer.insert(0, b"<");
+        buffer.insert(4, b"|");
+        buffer.insert(8, b">");
+        assert_eq!(content(&buffer), b"<abc|def>");
+        assert_eq!(buffer.len(), 9);
+        // Past the end is ignored
+        buffer.insert(10, b"!");
+        assert_eq!(content(&buffer), b"<abc|def>");
+    }
+
+    #[test]
+    fn insert_into_empty_buffer() {
+        let mut buffer = EditBuffer::new(ByteSource::Empty);
+        buffer.insert(0, b"ab");
+        buffer.insert(1, b"-");
+        assert_eq!(content(&buffer), b"a-b");
+    }
+
+    #[test]
+    fn delete_across_pieces() {
+        let mut buffer = buffer(b"abcdef");
+        buffer.insert(3, b"XYZ");
+        assert_eq!(content(&buffer), b"abcXYZdef");
+        // From the middle of the first file piece to the middle of the second
+        buffer.delete(1, 6);
+        assert_eq!(content(&buffer), b"aef");
+        assert_eq!(buffer.len(), 3);
+        // Clamped to the end
+        buffer.delete(2, 10);
+        assert_eq!(content(&buffer), b"ae");
+    }
+
+    #[test]
+    fn overwrite_past_the_end_keeps_the_length() {
+        let mut buffer = buffer(b"abcd");
+        buffer.overwrite(2, b"XYZ");
+        assert_eq!(content(&buffer), b"abXY");
+        buffer.overwrite(4, b"!");
+        assert_eq!(content(&buffer), b"abXY");
+        assert!(buffer.is_modified(2, 1));
+        assert!(!buffer.is_modified(0, 2));
+    }
+
+    #[test]
+    fn read_spans_several_pieces() {
+        let mut buffer = buffer(b"0123456789");
+        buffer.overwrite(2, b"a");
+        buffer.overwrite(5, b"b");
+        buffer.insert(8, b"cc");
+        assert_eq!(content(&buffer), b"01a34b67cc89");
+        assert_eq!(&buffer.read(1, 9)[..], b"1a34b67cc");
+        assert_eq!(&buffer.read(3, 2)[..], b"34");
+        assert_eq!(&buffer.read(10, 5)[..], b"89");
+        assert!(buffer.read(12, 1).is_empty());
+    }
+
+    #[test]
+    fn undo_redo_restore_length_and_dirty_state() {
+        let file = TempFile::new("undo", b"abcd");
+        let mut buffer = EditBuffer::new(ByteSource::open(&file.0).unwrap());
+        buffer.begin(0);
+        buffer.insert(0, b"xy");
+        assert!(buffer.is_dirty());
+        buffer.save(&file.0).unwrap();
+        assert!(!buffer.is_dirty());
+
+        buffer.begin(1);
+        buffer.delete(1, 3);
+        assert_eq!((buffer.len(), buffer.is_dirty()), (3, true));
+        assert_eq!(buffer.undo(), Some(1));
+        assert_eq!((buffer.len(), buffer.is_dirty()), (6, false));
+        assert_eq!(buffer.undo(), Some(0));
+        assert_eq!(content(&buffer), b"abcd");
+        assert!(buffer.is_dirty());
+        assert_eq!(buffer.undo(), None);
+
+        assert_eq!(buffer.redo(), Some(0));
+        assert_eq!(content(&buffer), b"xyabcd");
+        assert!(!buffer.is_dirty());
+        buffer.redo();
+        assert_eq!(content(&buffer), b"xcd");
+        assert_eq!(buffer.redo(), None);
+    }
+
+    #[test]
+    fn undo_and_redo_keep_appended_bytes() {
+        let grown = |bytes: &[u8]| ByteSource::Owned(Arc::new(bytes.to_vec()));
+        let mut buffer = buffer(b"abcd");
+        buffer.begin(0);
+        buffer.overwrite(0, b"x");
+        buffer.append_source(grown(b"abcdefgh"));
+        assert_eq!(content(&buffer), b"xbcdefgh");
+
+        assert_eq!(buffer.undo(), Some(0));
+        assert_eq!(content(&buffer), b"abcdefgh");
+        assert!(!buffer.is_dirty());
+
+        buffer.append_source(grown(b"abcdefghij"));
+        assert_eq!(buffer.redo(), Some(0));
+        assert_eq!(content(&buffer), b"xbcdefghij");
+        assert!(buffer.is_dirty());
+        buffer.undo();
+        assert_eq!(content(&buffer), b"abcdefghij");
+    }
+
+    #[test]
+    fn growing_source_extends_the_last_piece() {
+        let file: Vec<u8> = (0..100).collect();
+        let mut buffer = buffer(&file[..10]);
+        for end in 11..=50 {
+            buffer.append_source(ByteSource::Owned(Arc::new(file[..end].to_vec())));
+        }
+        assert_eq!(buffer.contents.pieces.len(), 1);
+
+        // An edit at the end is followed by a new piece, which then keeps growing
+        buffer.begin(50);
+        buffer.insert(50, b"!");
+        for end in 51..=100 {
+            buffer.append_source(ByteSource::Owned(Arc::new(file[..end].to_vec())));
+        }
+        assert_eq!(buffer.contents.pieces.len(), 3);
+        buffer.undo();
+        assert_eq!(content(&buffer), file);
+        assert_eq!(buffer.contents.pieces.len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn save_through_symlink_keeps_the_link() {
+        let target = TempFile::new("target", b"abc");
+        let link = TempFile(target.0.with_extension("link"));
+        std::os::unix::fs::symlink(&target.0, &link.0).unwrap();
+        let mut buffer = EditBuffer::new(ByteSource::open(&link.0).unwrap());
+        buffer.overwrite(0, b"x");
+        buffer.save(&link.0).unwrap();
+        assert!(fs::symlink_metadata(&link.0).unwrap().is_symlink());
+        assert_eq!(fs::read(&target.0).unwrap(), b"xbc");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn save_skips_a_link_planted_at_the_temp_name() {
+        let target = TempFile::new("planted", b"abc");
+        let victim = TempFile::new("victim", b"keep");
+        let name = target.0.file_name().unwrap().to_string_lossy();
+        let planted = TempFile(
+            target
+                .0
+                .with_file_name(format!(".{name}-{}-0.hexer-tmp", std::process::id())),
+        );
+        std::os::unix::fs::symlink(&victim.0, &planted.0).unwrap();
+        let mut buffer = EditBuffer::new(ByteSource::open(&target.0).unwrap());
+        buffer.overwrite(0, b"x");
+        buffer.save(&target.0).unwrap();
+        assert_eq!(fs::read(&target.0).unwrap(), b"xbc");
+        assert_eq!(fs::read(&victim.0).unwrap(), b"keep");
+    }
+}
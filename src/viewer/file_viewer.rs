@@ -1,14 +1,19 @@
-use super::common_dt::{DataType, DisplayType, Endianness};
+use super::address_map::AddressMap;
+use super::common_dt::{CharSet, DataType, DisplayType, Endianness, FloatNotation, FromBytes};
+use super::edit_buffer::EditBuffer;
+use super::search::Matches;
 use crate::utils::previous_power_of_two;
-use bytemuck::{AnyBitPattern, cast_slice};
 use num_traits::Float;
 use ratatui::prelude::{Buffer, Rect};
 use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
 use ratatui::widgets::{
     Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget,
     Widget,
 };
-use std::fmt::{Display, LowerExp, UpperHex};
+use std::borrow::Cow;
+use std::fmt::{Binary, Display, LowerExp, Octal, UpperHex};
+use std::ops::Range;
 
 #[cfg(debug_assertions)]
 use tracing::{info, instrument};
@@ -20,13 +25,22 @@ const SUPER_NUMS: [char; 10] = [
 const SUPER_MINUS: char = '\u{207B}';
 const SUB_10: &str = "\u{2081}\u{2080}";
 const SUB_16: &str = "\u{2081}\u{2086}";
+const SUB_8: &str = "\u{2088}";
+const SUB_2: &str = "\u{2082}";
 
 #[derive(Debug, Default)]
 pub struct FileViewer {
     data_type: DataType,
     display_type: DisplayType,
     endianness: Endianness,
-    content: Vec<u8>,
+    buffer: EditBuffer,
+    shift: usize,
+    matches: Matches,
+    charset: Option<CharSet>,
+    selection: Option<Range<usize>>,
+    address_map: AddressMap,
+    float_notation: FloatNotation,
+    precision: Option<usize>,
 }
 
 #[derive(Debug, Default)]
@@ -38,93 +52,136 @@ pub struct FileViewerState {
     total_rows: usize,
     set_cols: Option<usize>,
     scrollbar: Option<ScrollbarState>,
+    data_size: usize,
+    shift: usize,
+    len: usize,
+    cursor: usize,
+    past_end: bool,
 }
 
 impl FileViewerState {
-    pub fn move_down(&mut self) {
-        if self.total_rows > self.rows && self.row_offset < (self.total_rows - self.rows) {
-            self.row_offset += 1;
-            if let Some(ref mut scrollbar_state) = self.scrollbar {
-                scrollbar_state.next();
-            }
+    // Byte offset of the selected cell
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    // The cursor snaps to its cell and is scrolled into view on the next render
+    pub fn goto_offset(&mut self, offset: usize) {
+        self.cursor = offset;
+    }
+
+    fn stride(&self) -> usize {
+        self.set_cols.unwrap_or(self.cols)
+    }
+
+    // A fixed number of cells per row, `None` fits as many as the width allows
+    pub fn set_cols(&mut self, cols: Option<usize>) {
+        self.set_cols = cols;
+    }
+
+    // Inserting needs a cell just past the last byte to append at
+    pub fn set_past_end(&mut self, past_end: bool) {
+        self.past_end = past_end;
+    }
+
+    // Keeps cursor moves in bounds between an edit and the next render
+    pub fn set_len(&mut self, len: usize) {
+        self.len = len;
+    }
+
+    fn last(&self) -> usize {
+        match self.past_end {
+            true => self.len,
+            false => self.len.saturating_sub(1),
         }
     }
 
-    pub fn move_up(&mut self) {
-        if self.row_offset > 0 {
-            self.row_offset -= 1;
-            if let Some(ref mut scrollbar_state) = self.scrollbar {
-                scrollbar_state.prev();
+    fn align_cursor(&mut self) {
+        let cursor = self.cursor.min(self.last()).saturating_sub(self.shift);
+        self.cursor = self.shift + cursor / self.data_size * self.data_size;
+    }
+
+    fn scroll_to(&mut self, offset: usize) {
+        let stride = self.stride();
+        let idx = offset.saturating_sub(self.shift) / self.data_size;
+        let (row, col) = (idx / stride, idx % stride);
+        if row < self.row_offset {
+            self.row_offset = row;
+        } else if row >= self.row_offset + self.rows {
+            self.row_offset = (row + 1).saturating_sub(self.rows);
+        }
+        self.row_offset = self
+            .row_offset
+            .min(self.total_rows.saturating_sub(self.rows));
+        if self.set_cols.is_some() {
+            if col < self.col_offset {
+                self.col_offset = col;
+            } else if col >= self.col_offset + self.cols {
+                self.col_offset = (col + 1).saturating_sub(self.cols);
             }
         }
+        if let Some(scroll) = self.scrollbar {
+            self.scrollbar = Some(scroll.position(self.row_offset));
+        }
     }
 
-    pub fn move_right(&mut self) {
-        if let Some(col) = self.set_cols {
-            if col > self.cols && self.col_offset < (col - self.cols) {
-                self.col_offset += 1;
-            }
+    fn move_cursor(&mut self, delta: isize) {
+        if let Some(cursor) = self.cursor.checked_add_signed(delta)
+            && cursor <= self.last()
+        {
+            self.cursor = cursor;
         }
     }
 
+    pub fn move_down(&mut self) {
+        self.move_cursor((self.stride() * self.data_size) as isize);
+    }
+
+    pub fn move_up(&mut self) {
+        self.move_cursor(-((self.stride() * self.data_size) as isize));
+    }
+
+    pub fn move_right(&mut self) {
+        self.move_cursor(self.data_size as isize);
+    }
+
     pub fn move_left(&mut self) {
-        if self.set_cols.is_some() && self.col_offset > 0 {
-            self.col_offset -= 1;
-        }
+        self.move_cursor(-(self.data_size as isize));
+    }
+
+    pub fn at_bottom(&self) -> bool {
+        self.cursor + self.data_size.max(1) > self.last()
     }
 
     pub fn goto_top(&mut self) {
-        self.row_offset = 0;
-        if let Some(ref mut scrollbar_state) = self.scrollbar {
-            scrollbar_state.first();
-        }
+        self.cursor = 0;
     }
     pub fn goto_bottom(&mut self) {
-        if self.total_rows > self.rows {
-            self.row_offset = self.total_rows - self.rows;
-        }
-        if let Some(ref mut scrollbar_state) = self.scrollbar {
-            scrollbar_state.last();
-        }
+        self.cursor = self.last();
     }
     pub fn goto_start(&mut self) {
-        self.col_offset = 0;
+        let row_len = self.stride() * self.data_size;
+        if row_len > 0 {
+            let idx = self.cursor.saturating_sub(self.shift);
+            self.cursor = self.shift + idx - idx % row_len;
+        }
     }
     pub fn goto_end(&mut self) {
-        if let Some(col) = self.set_cols {
-            if col > self.cols {
-                self.col_offset = col - self.cols;
-            }
-        }
+        self.goto_start();
+        self.cursor += (self.stride().saturating_sub(1)) * self.data_size;
     }
 
     pub fn scroll_down(&mut self) {
-        if self.total_rows > self.rows * 3 / 2 {
-            if self.row_offset < (self.total_rows - self.rows - self.rows / 2) {
-                self.row_offset += self.rows / 2;
-                if let Some(scroll) = self.scrollbar {
-                    self.scrollbar = Some(scroll.position(self.row_offset));
-                }
-            } else {
-                self.row_offset = self.total_rows - self.rows;
-                if let Some(ref mut scrollbar_state) = self.scrollbar {
-                    scrollbar_state.last();
-                }
-            }
-        }
+        let step = self.rows / 2;
+        self.row_offset = (self.row_offset + step).min(self.total_rows.saturating_sub(self.rows));
+        self.cursor += step * self.stride() * self.data_size;
     }
     pub fn scroll_up(&mut self) {
-        if self.row_offset > self.rows / 2 {
-            self.row_offset -= self.rows / 2;
-            if let Some(scroll) = self.scrollbar {
-                self.scrollbar = Some(scroll.position(self.row_offset));
-            }
-        } else {
-            self.row_offset = 0;
-            if let Some(ref mut scrollbar_state) = self.scrollbar {
-                scrollbar_state.first();
-            }
-        }
+        let step = self.rows / 2;
+        self.row_offset = self.row_offset.saturating_sub(step);
+        self.cursor = self
+            .cursor
+            .saturating_sub(step * self.stride() * self.data_size);
     }
 }
 
@@ -135,38 +192,56 @@ impl StatefulWidget for &FileViewer {
         instrument(skip(self, buf, state), name = "FileViewer::render")
     )]
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
-        let (cols, data_width, data_size) = self.calc_cols(area);
-        let areas = simple_layout_solver(area, cols, data_width);
+        let (fit, data_width, data_size) = self.calc_cols(area);
+        // Fitted rows are a power of two wide, a fixed count wider than the screen scrolls sideways
+        let cols = match state.set_cols {
+            Some(set) => fit.min(set.min(u16::MAX as usize) as u16),
+            None => previous_power_of_two(fit),
+        };
+        let text_width = match self.charset {
+            Some(_) => cols * data_size as u16,
+            None => 0,
+        };
+        let areas = simple_layout_solver(area, cols, data_width, text_width, self.address_digits());
 
         #[cfg(debug_assertions)]
         info!(?areas);
 
-        state.rows = area.height as usize - 2;
+        state.rows = (area.height as usize).saturating_sub(2);
 
         state.cols = cols as usize;
+        let len = (self.buffer.len() + state.past_end as usize).saturating_sub(self.shift);
         state.total_rows = match state.set_cols {
-            Some(col) => self.content.len() / (data_size as usize * col),
-            None => self.content.len() / (data_size as usize * state.cols),
+            Some(col) => len.div_ceil(data_size as usize * col),
+            None => len.div_ceil(data_size as usize * state.cols),
         };
 
-        self.render_header(cols, &areas[..], buf);
-        self.render_data(
-            state.row_offset,
-            state.col_offset,
-            state.rows,
-            state.cols,
-            &areas[..],
-            buf,
-        );
+        state.data_size = data_size as usize;
+        state.shift = self.shift;
+        state.len = self.buffer.len();
 
         state.scrollbar = Some(match state.scrollbar {
             Some(scroll) => scroll.content_length(state.total_rows),
             None => ScrollbarState::new(state.total_rows),
         });
+        state.col_offset = state
+            .col_offset
+            .min(state.stride().saturating_sub(state.cols));
+        state.align_cursor();
+        state.scroll_to(state.cursor);
+
+        self.render_header(cols, state.col_offset, &areas[..], buf);
+        self.render_data(state, &areas[..], buf);
+        if let Some(charset) = self.charset {
+            self.render_text(charset, state, areas[state.cols + 2], buf);
+        }
 
         let mut scrollbar_area = areas[state.cols + 1];
         scrollbar_area.height = state.rows as u16 + 1;
 
+        if scrollbar_area.is_empty() {
+            return;
+        }
         let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
             .begin_symbol(Some("↑"))
             .end_symbol(Some("↓"));
@@ -175,24 +250,79 @@ impl StatefulWidget for &FileViewer {
 }
 
 impl FileViewer {
-    pub fn set_content(&mut self, content: Vec<u8>) {
-        self.content = content;
+    pub fn set_buffer(&mut self, buffer: EditBuffer) {
+        self.buffer = buffer;
+    }
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+    pub fn buffer(&self) -> &EditBuffer {
+        &self.buffer
+    }
+    pub fn buffer_mut(&mut self) -> &mut EditBuffer {
+        &mut self.buffer
+    }
+    pub fn set_matches(&mut self, matches: Matches) {
+        self.matches = matches;
+    }
+    pub fn matches(&self) -> &Matches {
+        &self.matches
+    }
+    pub fn matches_mut(&mut self) -> &mut Matches {
+        &mut self.matches
+    }
+    pub fn address_map(&self) -> &AddressMap {
+        &self.address_map
+    }
+    pub fn set_address_map(&mut self, address_map: AddressMap) {
+        self.address_map = address_map;
+    }
+    pub fn set_selection(&mut self, selection: Option<Range<usize>>) {
+        self.selection = selection;
     }
     pub fn set_display_type(&mut self, display_type: DisplayType) {
         self.display_type = display_type;
     }
     pub fn set_data_type(&mut self, data_type: DataType) {
+        self.shift %= data_type.size();
         self.data_type = data_type;
     }
+    pub fn charset(&self) -> Option<CharSet> {
+        self.charset
+    }
+    pub fn set_charset(&mut self, charset: Option<CharSet>) {
+        self.charset = charset;
+    }
+    pub fn shift(&self) -> usize {
+        self.shift
+    }
+    // Moves element boundaries so records starting at odd offsets line up
+    pub fn shift_forward(&mut self) {
+        self.shift = (self.shift + 1) % self.data_type.size();
+    }
+    pub fn shift_backward(&mut self) {
+        let size = self.data_type.size();
+        self.shift = (self.shift + size - 1) % size;
+    }
     pub fn set_endianness(&mut self, endianness: Endianness) {
         self.endianness = endianness;
     }
+    pub fn set_float_notation(&mut self, float_notation: FloatNotation) {
+        self.float_notation = float_notation;
+    }
+    pub fn set_precision(&mut self, precision: Option<usize>) {
+        self.precision = precision;
+    }
+    fn precision(&self) -> usize {
+        self.precision
+            .unwrap_or_else(|| self.data_type.default_precision())
+    }
 
     #[cfg_attr(
         debug_assertions,
         instrument(skip(self, buf), name = "FileViewer::render_header")
     )]
-    fn render_header(&self, cols: u16, area: &[Rect], buf: &mut Buffer) {
+    fn render_header(&self, cols: u16, col_offset: usize, area: &[Rect], buf: &mut Buffer) {
         let fg = Color::LightCyan;
         let b = Block::default().borders(Borders::RIGHT | Borders::LEFT);
         Paragraph::new(" Address ")
@@ -201,7 +331,7 @@ impl FileViewer {
             .render(area[0], buf);
 
         for i in 0..cols {
-            Paragraph::new(format!("{i}"))
+            Paragraph::new(format!("{}", col_offset + i as usize))
                 .centered()
                 .style(Style::default().fg(fg).bold())
                 .render(area[i as usize + 1], buf);
@@ -210,77 +340,111 @@ impl FileViewer {
             .borders(Borders::RIGHT)
             .bg(Color::Reset)
             .fg(fg);
-        b.render(area[cols as usize + 1], buf);
+        b.clone().render(area[cols as usize + 1], buf);
+
+        if let Some(charset) = self.charset {
+            Paragraph::new(charset.to_string())
+                .centered()
+                .style(Style::default().fg(fg).bold())
+                .block(b)
+                .render(area[cols as usize + 2], buf);
+        }
+    }
+
+    // Printable characters of each row, in sync with the cells shown in the grid
+    fn render_text(&self, charset: CharSet, state: &FileViewerState, area: Rect, buf: &mut Buffer) {
+        let (row_offset, col_offset, rows, cols) =
+            (state.row_offset, state.col_offset, state.rows, state.cols);
+        let stride = state.stride();
+        let size = self.data_type.size();
+        for row in 0..rows {
+            let (row_start, bytes) =
+                self.row_bytes(size, row_offset + row, stride, col_offset, cols);
+            if bytes.is_empty() {
+                break;
+            }
+            let mut line = Line::default();
+            let mut i = 0;
+            while i < bytes.len() {
+                let (text, len) = decode_char(charset, &bytes[i..]);
+                let offset = row_start + i;
+                let cursor = state.cursor..state.cursor + size;
+                let fg = match self.buffer.is_modified(offset, len) {
+                    true => Color::LightRed,
+                    false => Color::Green,
+                };
+                let style = match self.matches.hit(offset..offset + 1) {
+                    _ if cursor.contains(&offset) => {
+                        Style::default().fg(Color::Black).bg(Color::LightYellow)
+                    }
+                    _ if self.selected(offset) => {
+                        Style::default().fg(Color::White).bg(Color::DarkGray)
+                    }
+                    Some(true) => Style::default().fg(Color::Black).bg(Color::LightMagenta),
+                    Some(false) => Style::default().fg(fg).bg(Color::Blue),
+                    None if text == "." && fg == Color::Green => {
+                        Style::default().fg(Color::DarkGray)
+                    }
+                    None => Style::default().fg(fg),
+                };
+                line.push_span(Span::styled(text, style));
+                i += len;
+            }
+            let row_area = Rect {
+                y: area.y + 1 + row as u16,
+                ..area
+            };
+            Paragraph::new(line)
+                .block(
+                    Block::default()
+                        .borders(Borders::RIGHT)
+                        .fg(Color::LightCyan),
+                )
+                .render(row_area, buf);
+        }
     }
 
     #[cfg_attr(
         debug_assertions,
         instrument(skip(self, buf, areas), name = "FileViewer::render_data")
     )]
-    fn render_data(
-        &self,
-        row_offset: usize,
-        col_offset: usize,
-        rows: usize,
-        cols: usize,
-        areas: &[Rect],
-        buf: &mut Buffer,
-    ) {
+    fn render_data(&self, state: &FileViewerState, areas: &[Rect], buf: &mut Buffer) {
         match self.data_type {
-            DataType::U8 => {
-                self.render_int_data::<u8>(row_offset, col_offset, rows, cols, areas, buf)
-            }
-            DataType::I8 => {
-                self.render_int_data::<i8>(row_offset, col_offset, rows, cols, areas, buf)
-            }
-            DataType::U16 => {
-                self.render_int_data::<u16>(row_offset, col_offset, rows, cols, areas, buf)
-            }
-            DataType::I16 => {
-                self.render_int_data::<i16>(row_offset, col_offset, rows, cols, areas, buf)
-            }
-            DataType::U32 => {
-                self.render_int_data::<u32>(row_offset, col_offset, rows, cols, areas, buf)
-            }
-            DataType::I32 => {
-                self.render_int_data::<i32>(row_offset, col_offset, rows, cols, areas, buf)
-            }
-            DataType::U64 => {
-                self.render_int_data::<u64>(row_offset, col_offset, rows, cols, areas, buf)
-            }
-            DataType::I64 => {
-                self.render_int_data::<i64>(row_offset, col_offset, rows, cols, areas, buf)
-            }
-            DataType::F32 => {
-                self.render_float_data::<f32, 5>(row_offset, col_offset, rows, cols, areas, buf)
-            }
-            DataType::F64 => {
-                self.render_float_data::<f64, 10>(row_offset, col_offset, rows, cols, areas, buf)
-            }
+            DataType::U8 => self.render_int_data::<u8>(state, areas, buf),
+            DataType::I8 => self.render_int_data::<i8>(state, areas, buf),
+            DataType::U16 => self.render_int_data::<u16>(state, areas, buf),
+            DataType::I16 => self.render_int_data::<i16>(state, areas, buf),
+            DataType::U32 => self.render_int_data::<u32>(state, areas, buf),
+            DataType::I32 => self.render_int_data::<i32>(state, areas, buf),
+            DataType::U64 => self.render_int_data::<u64>(state, areas, buf),
+            DataType::I64 => self.render_int_data::<i64>(state, areas, buf),
+            DataType::F32 => self.render_float_data::<f32>(state, areas, buf),
+            DataType::F64 => self.render_float_data::<f64>(state, areas, buf),
         }
     }
 
-    fn render_int_data<T>(
-        &self,
-        row_offset: usize,
-        col_offset: usize,
-        rows: usize,
-        cols: usize,
-        areas: &[Rect],
-        buf: &mut Buffer,
-    ) where
-        T: AnyBitPattern + Display + UpperHex,
+    fn render_int_data<T>(&self, state: &FileViewerState, areas: &[Rect], buf: &mut Buffer)
+    where
+        T: FromBytes + Display + UpperHex + Octal + Binary,
     {
+        let (row_offset, col_offset, rows, cols) =
+            (state.row_offset, state.col_offset, state.rows, state.cols);
+        let stride = state.stride();
         let fg = Color::LightCyan;
         let mut y = areas[0].y;
-        // let content = &self.content;
-        let content: &[T] = cast_slice(&self.content);
-        let content_len = content.len();
+        let digits = self.address_digits() as usize;
         'outer_loop: for row in row_offset..(rows + row_offset) {
             y += 1;
             let mut area = areas[0];
             area.y = y;
-            Paragraph::new(format!(" {:08X} ", row * cols))
+            let address = match self
+                .address_map
+                .to_address(self.shift + row * stride * T::SIZE)
+            {
+                Some(address) => format!(" {address:0digits$X} "),
+                None => format!(" {:-<digits$} ", ""),
+            };
+            Paragraph::new(address)
                 .block(
                     Block::default()
                         .borders(Borders::RIGHT | Borders::LEFT)
@@ -296,24 +460,49 @@ impl FileViewer {
                 .bg(Color::Reset)
                 .fg(fg)
                 .render(area, buf);
-            for col in col_offset..(cols + col_offset) {
-                area = areas[(col - col_offset) + 1];
+            let (start, content) = self.row_bytes(T::SIZE, row, stride, col_offset, cols);
+            for idx in 0..cols {
+                area = areas[idx + 1];
                 area.y = y;
 
-                if (row * cols + col) >= content_len {
+                if idx * T::SIZE >= content.len() {
+                    if start + idx * T::SIZE == state.cursor {
+                        render_end_cursor(area, buf);
+                    }
+                    break 'outer_loop;
+                }
+                if (idx + 1) * T::SIZE > content.len() {
+                    render_partial(&content[idx * T::SIZE..], T::SIZE, area, buf);
                     break 'outer_loop;
                 }
                 match self.display_type {
                     DisplayType::Decimal => {
-                        Paragraph::new(format!("{}{SUB_10}", content[row * cols + col]))
+                        Paragraph::new(format!("{}{SUB_10}", self.decode::<T>(&content, idx)))
                             .right_aligned()
-                            .style(Style::default().fg(Color::Yellow))
+                            .style(self.cell_style(start + idx * T::SIZE, T::SIZE, state.cursor))
                             .render(area, buf)
                     }
                     DisplayType::HexaDecimal => {
-                        Paragraph::new(format!("{:X}{SUB_16}", content[row * cols + col]))
+                        Paragraph::new(format!("{:X}{SUB_16}", self.decode::<T>(&content, idx)))
                             .right_aligned()
-                            .style(Style::default().fg(Color::Yellow))
+                            .style(self.cell_style(start + idx * T::SIZE, T::SIZE, state.cursor))
+                            .render(area, buf)
+                    }
+                    DisplayType::Octal => {
+                        Paragraph::new(format!("{:o}{SUB_8}", self.decode::<T>(&content, idx)))
+                            .right_aligned()
+                            .style(self.cell_style(start + idx * T::SIZE, T::SIZE, state.cursor))
+                            .render(area, buf)
+                    }
+                    DisplayType::Binary => {
+                        let bits = format!(
+                            "{:0width$b}",
+                            self.decode::<T>(&content, idx),
+                            width = T::SIZE * 8
+                        );
+                        Paragraph::new(format!("{}{SUB_2}", group_nibbles(&bits)))
+                            .right_aligned()
+                            .style(self.cell_style(start + idx * T::SIZE, T::SIZE, state.cursor))
                             .render(area, buf)
                     }
                 }
@@ -321,26 +510,30 @@ impl FileViewer {
         }
     }
 
-    fn render_float_data<T, const PREC: usize>(
-        &self,
-        row_offset: usize,
-        col_offset: usize,
-        rows: usize,
-        cols: usize,
-        areas: &[Rect],
-        buf: &mut Buffer,
-    ) where
-        T: AnyBitPattern + Display + Float + LowerExp,
+    fn render_float_data<T>(&self, state: &FileViewerState, areas: &[Rect], buf: &mut Buffer)
+    where
+        T: FromBytes + Display + Float + LowerExp,
     {
+        let (row_offset, col_offset, rows, cols) =
+            (state.row_offset, state.col_offset, state.rows, state.cols);
+        let stride = state.stride();
         let fg = Color::LightCyan;
         let mut y = areas[0].y;
-        let content: &[T] = cast_slice(&self.content);
-        let content_len = content.len();
+        let digits = self.address_digits() as usize;
+        let precision = self.precision();
+        let width = self.float_notation.width(self.data_type, precision);
         'outer_loop: for row in row_offset..(rows + row_offset) {
             y += 1;
             let mut area = areas[0];
             area.y = y;
-            Paragraph::new(format!(" {:08X} ", row * cols))
+            let address = match self
+                .address_map
+                .to_address(self.shift + row * stride * T::SIZE)
+            {
+                Some(address) => format!(" {address:0digits$X} "),
+                None => format!(" {:-<digits$} ", ""),
+            };
+            Paragraph::new(address)
                 .block(
                     Block::default()
                         .borders(Borders::RIGHT | Borders::LEFT)
@@ -356,28 +549,103 @@ impl FileViewer {
                 .bg(Color::Reset)
                 .fg(fg)
                 .render(area, buf);
-            for col in col_offset..(cols + col_offset) {
-                area = areas[(col - col_offset) + 1];
+            let (start, content) = self.row_bytes(T::SIZE, row, stride, col_offset, cols);
+            for idx in 0..cols {
+                area = areas[idx + 1];
                 area.y = y;
 
-                if (row * cols + col) >= content_len {
+                if idx * T::SIZE >= content.len() {
+                    if start + idx * T::SIZE == state.cursor {
+                        render_end_cursor(area, buf);
+                    }
+                    break 'outer_loop;
+                }
+                if (idx + 1) * T::SIZE > content.len() {
+                    render_partial(&content[idx * T::SIZE..], T::SIZE, area, buf);
                     break 'outer_loop;
                 }
 
-                Paragraph::new(format_scientific_unicode(content[row * cols + col], PREC))
+                // Other than decimal the raw IEEE 754 bits are shown, padded so the fields line up
+                let bits = self
+                    .data_type
+                    .decode_bits(&content[idx * T::SIZE..], &self.endianness);
+                let text = match self.display_type {
+                    DisplayType::Decimal => format_float(
+                        self.decode::<T>(&content, idx),
+                        bits,
+                        self.float_notation,
+                        precision,
+                        width,
+                    ),
+                    DisplayType::HexaDecimal => {
+                        format!("{bits:0digits$X}{SUB_16}", digits = T::SIZE * 2)
+                    }
+                    DisplayType::Octal => format!("{bits:o}{SUB_8}"),
+                    DisplayType::Binary => {
+                        let bits = format!("{bits:0width$b}", width = T::SIZE * 8);
+                        format!("{}{SUB_2}", group_nibbles(&bits))
+                    }
+                };
+                Paragraph::new(text)
                     .right_aligned()
-                    .style(Style::default().fg(Color::Yellow))
+                    .style(self.cell_style(start + idx * T::SIZE, T::SIZE, state.cursor))
                     .render(area, buf);
             }
         }
     }
 
+    // Only the cells on screen are read, rows wider than the screen are never read whole
+    fn row_bytes(
+        &self,
+        size: usize,
+        row: usize,
+        stride: usize,
+        col_offset: usize,
+        cols: usize,
+    ) -> (usize, Cow<'_, [u8]>) {
+        let start = self.shift + (row * stride + col_offset) * size;
+        (start, self.buffer.read(start, cols * size))
+    }
+
+    fn cell_style(&self, offset: usize, size: usize, cursor: usize) -> Style {
+        let style = match self.buffer.is_modified(offset, size) {
+            true => Style::default().fg(Color::LightRed),
+            false => Style::default().fg(Color::Yellow),
+        };
+        match self.matches.hit(offset..offset + size) {
+            _ if offset == cursor => style.fg(Color::Black).bg(Color::LightYellow),
+            _ if self.selected(offset) => style.fg(Color::White).bg(Color::DarkGray),
+            Some(true) => style.fg(Color::Black).bg(Color::LightMagenta),
+            Some(false) => style.bg(Color::Blue),
+            None => style,
+        }
+    }
+
+    // Hex digits of the address column, widened once offsets no longer fit in 32 or 48 bits
+    fn address_digits(&self) -> u16 {
+        match self.address_map.end_address(self.buffer.len()) as u64 {
+            0..=0xFFFF_FFFF => 8,
+            0x1_0000_0000..=0xFFFF_FFFF_FFFF => 12,
+            _ => 16,
+        }
+    }
+
+    fn selected(&self, offset: usize) -> bool {
+        self.selection
+            .as_ref()
+            .is_some_and(|selection| selection.contains(&offset))
+    }
+
+    fn decode<T: FromBytes>(&self, content: &[u8], idx: usize) -> T {
+        T::from_bytes(&content[idx * T::SIZE..], &self.endianness)
+    }
+
     #[cfg_attr(
         debug_assertions,
         instrument(skip(self), name = "FileViewer::calc_cols")
     )]
     fn calc_cols(&self, area: Rect) -> (u16, u16, u8) {
-        let address_size = 8 + 3 + 1; // 32bit address + 1 margin + 1 sep + 1 space
+        let address_size = self.address_digits() + 3 + 1; // address + 1 margin + 1 sep + 1 space
         use DataType::*;
         use DisplayType::*;
         let (mut data_width, data_size) = match (&self.data_type, &self.display_type) {
@@ -397,22 +665,57 @@ impl FileViewer {
             (I32, HexaDecimal) => (8, 4),
             (U64, HexaDecimal) => (16, 8),
             (I64, HexaDecimal) => (16, 8),
-            (F32, Decimal) => (14, 4),
-            (F64, Decimal) => (23, 8),
-            (F32, HexaDecimal) => (4, 4),
-            (F64, HexaDecimal) => (8, 8),
+            (U8, Octal) => (3, 1),
+            (I8, Octal) => (3, 1),
+            (U16, Octal) => (6, 2),
+            (I16, Octal) => (6, 2),
+            (U32, Octal) => (11, 4),
+            (I32, Octal) => (11, 4),
+            (U64, Octal) => (22, 8),
+            (I64, Octal) => (22, 8),
+            // Binary cells are grouped by nibble: 8 bits + 1 separator per byte, minus the last
+            (U8, Binary) => (9, 1),
+            (I8, Binary) => (9, 1),
+            (U16, Binary) => (19, 2),
+            (I16, Binary) => (19, 2),
+            (U32, Binary) => (39, 4),
+            (I32, Binary) => (39, 4),
+            (U64, Binary) => (79, 8),
+            (I64, Binary) => (79, 8),
+            (F32 | F64, Decimal) => (
+                self.float_notation.width(self.data_type, self.precision()) as u16,
+                self.data_type.size() as u8,
+            ),
+            (F32, HexaDecimal) => (8, 4),
+            (F64, HexaDecimal) => (16, 8),
+            (F32, Octal) => (11, 4),
+            (F64, Octal) => (22, 8),
+            (F32, Binary) => (39, 4),
+            (F64, Binary) => (79, 8),
         };
         data_width += 2 + 1; // 2 is for base + 1 for spacing
-        let num_cols = (area.width - address_size - 2) / data_width;
+        // The text pane takes one char per byte + 1 space + 1 border
+        let (text_size, text_border) = match self.charset {
+            Some(_) => (data_size as u16, 2),
+            None => (0, 0),
+        };
+        let num_cols =
+            area.width.saturating_sub(address_size + 2 + text_border) / (data_width + text_size);
         #[cfg(debug_assertions)]
         info!(num_cols, data_width);
 
-        (previous_power_of_two(num_cols), data_width, data_size)
+        (num_cols.max(1), data_width, data_size)
     }
 }
 
 #[cfg_attr(debug_assertions, instrument)]
-fn simple_layout_solver(area: Rect, cols: u16, data_size: u16) -> Vec<Rect> {
+fn simple_layout_solver(
+    area: Rect,
+    cols: u16,
+    data_size: u16,
+    text_width: u16,
+    address_size: u16,
+) -> Vec<Rect> {
     let mut rects = vec![];
     let Rect {
         mut x,
@@ -421,18 +724,21 @@ fn simple_layout_solver(area: Rect, cols: u16, data_size: u16) -> Vec<Rect> {
         height: _,
     } = area;
 
-    let address_size = 8;
     let address_padding = 2;
     let address_border = 2;
     let total_address_size = address_size + address_padding + address_border;
     let right_border = 1;
+    let text_pane = match text_width {
+        0 => 0,
+        _ => text_width + 2,
+    };
+    let used = total_address_size + cols * data_size + right_border + text_pane;
 
-    let spacing = (width - (total_address_size + cols * data_size + right_border)) / (cols + 1);
+    let spacing = width.saturating_sub(used) / (cols + 1);
     #[cfg(debug_assertions)]
     info!(spacing);
 
-    let remaining_space =
-        width - (total_address_size + cols * data_size + right_border) - (cols + 1) * spacing;
+    let remaining_space = width.saturating_sub(used) - (cols + 1) * spacing;
     let front_margin = remaining_space / 2;
     #[cfg(debug_assertions)]
     info!(front_margin);
@@ -470,10 +776,132 @@ fn simple_layout_solver(area: Rect, cols: u16, data_size: u16) -> Vec<Rect> {
         width: 1,
         height: 1,
     });
-    rects
+    if text_pane > 0 {
+        rects.push(Rect {
+            x: x + 2,
+            y,
+            width: text_width + 1,
+            height: 1,
+        });
+    }
+    // Terminals too narrow for a single column clip instead of drawing outside the buffer
+    rects.iter().map(|rect| rect.intersection(area)).collect()
+}
+
+// Text for the char starting at `bytes[0]` and how many bytes it covers. Multi-byte chars are
+// padded so every byte still takes one cell.
+fn decode_char(charset: CharSet, bytes: &[u8]) -> (String, usize) {
+    let b = bytes[0];
+    match charset {
+        CharSet::Ascii | CharSet::Latin1 if (0x20..0x7F).contains(&b) => ((b as char).into(), 1),
+        // Soft hyphen has no width
+        CharSet::Latin1 if b >= 0xA0 && b != 0xAD => ((b as char).into(), 1),
+        CharSet::Utf8 if b >= 0x80 => {
+            let len = match b {
+                0xC2..=0xDF => 2,
+                0xE0..=0xEF => 3,
+                0xF0..=0xF4 => 4,
+                _ => 1,
+            };
+            let c = bytes
+                .get(..len)
+                .and_then(|seq| std::str::from_utf8(seq).ok())
+                .and_then(|seq| seq.chars().next())
+                .filter(|c| !c.is_control());
+            match c {
+                Some(c) => {
+                    let text = c.to_string();
+                    let width = Span::raw(text.as_str()).width();
+                    (text + &" ".repeat(len.saturating_sub(width)), len)
+                }
+                None => (String::from("."), 1),
+            }
+        }
+        CharSet::Utf8 if (0x20..0x7F).contains(&b) => ((b as char).into(), 1),
+        _ => (String::from("."), 1),
+    }
+}
+
+fn render_end_cursor(area: Rect, buf: &mut Buffer) {
+    Paragraph::new("__")
+        .right_aligned()
+        .style(Style::default().fg(Color::Black).bg(Color::LightYellow))
+        .render(area, buf);
+}
+
+// A trailing element cut short by the end of the file: known bytes in file order, `??` for the rest
+fn render_partial(bytes: &[u8], size: usize, area: Rect, buf: &mut Buffer) {
+    let mut text: String = bytes.iter().map(|b| format!("{b:02X}")).collect();
+    text.push_str(&"??".repeat(size - bytes.len()));
+    Paragraph::new(text)
+        .right_aligned()
+        .style(Style::default().fg(Color::DarkGray).italic())
+        .render(area, buf);
+}
+
+// `10100011` -> `1010_0011`, the bits always come in whole nibbles
+pub fn group_nibbles(bits: &str) -> String {
+    let nibbles: Vec<&str> = (0..bits.len())
+        .step_by(4)
+        .map(|i| &bits[i..(i + 4).min(bits.len())])
+        .collect();
+    nibbles.join("_")
+}
+
+// Fixed notation falls back to scientific for values that do not fit in `width`
+pub fn format_float<T>(
+    value: T,
+    bits: u64,
+    notation: FloatNotation,
+    precision: usize,
+    width: usize,
+) -> String
+where
+    T: Float + Display + LowerExp,
+{
+    match notation {
+        FloatNotation::Scientific => format_scientific_unicode(value, precision),
+        FloatNotation::Fixed => {
+            let fixed = format!("{value:.precision$}");
+            match value.is_finite() && fixed.chars().count() <= width {
+                true => fixed,
+                false => format_scientific_unicode(value, precision),
+            }
+        }
+        FloatNotation::HexFloat => format_hex_float(bits, size_of::<T>()),
+    }
+}
+
+// C99 `%a`: 12.0 is `0x1.8p+3`, subnormals start with `0x0.`
+fn format_hex_float(bits: u64, size: usize) -> String {
+    let (mantissa_bits, exponent_bits) = match size {
+        4 => (23usize, 8usize),
+        _ => (52, 11),
+    };
+    let max_exponent = (1u64 << exponent_bits) - 1;
+    let bias = (max_exponent >> 1) as i64;
+    let sign = match (bits >> (mantissa_bits + exponent_bits)) & 1 {
+        1 => "-",
+        _ => "",
+    };
+    let exponent = (bits >> mantissa_bits) & max_exponent;
+    let mantissa = bits & ((1 << mantissa_bits) - 1);
+    let digits = mantissa_bits.div_ceil(4);
+    let fraction = format!("{:0digits$x}", mantissa << (digits * 4 - mantissa_bits));
+    let fraction = match fraction.trim_end_matches('0') {
+        "" => String::new(),
+        fraction => format!(".{fraction}"),
+    };
+    match (exponent, mantissa) {
+        (0, 0) => format!("{sign}0x0p+0"),
+        (0, _) => format!("{sign}0x0{fraction}p{:+}", 1 - bias),
+        (e, 0) if e == max_exponent => format!("{sign}∞"),
+        (e, _) if e == max_exponent => String::from("NAN"),
+        (e, _) => format!("{sign}0x1{fraction}p{:+}", e as i64 - bias),
+    }
 }
 
-fn format_scientific_unicode<T>(val: T, precision: usize) -> String
+pub fn format_scientific_unicode<T>(val: T, precision: usize) -> String
 where
     T: Float + Display + LowerExp,
 {
@@ -0,0 +1,138 @@
+use super::common_dt::{DataType, Endianness, FromBytes};
+use super::file_viewer::format_scientific_unicode;
+use ratatui::prelude::{Buffer, Rect};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph, Widget};
+use std::fmt;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Radix {
+    #[default]
+    Decimal,
+    HexaDecimal,
+    Binary,
+}
+
+impl Radix {
+    pub fn next(self) -> Self {
+        match self {
+            Radix::Decimal => Radix::HexaDecimal,
+            Radix::HexaDecimal => Radix::Binary,
+            Radix::Binary => Radix::Decimal,
+        }
+    }
+}
+
+// Decodes the bytes under the cursor as every data type, in both byte orders
+pub struct Inspector<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    radix: Radix,
+}
+
+impl<'a> Inspector<'a> {
+    pub fn new(bytes: &'a [u8], offset: usize, radix: Radix) -> Self {
+        Inspector {
+            bytes,
+            offset,
+            radix,
+        }
+    }
+
+    // Width of the panel including borders, wide enough for a 64-bit value in the radix
+    pub fn width(radix: Radix) -> u16 {
+        let value = match radix {
+            Radix::Decimal => 25,
+            Radix::HexaDecimal => 19,
+            Radix::Binary => 72,
+        };
+        value + 11
+    }
+
+    fn format(&self, data_type: DataType, endianness: &Endianness) -> String {
+        let size = data_type.size();
+        if self.bytes.len() < size {
+            return String::from("-");
+        }
+        let bytes = &self.bytes[..size];
+        // Floats show their raw IEEE 754 bits outside of decimal
+        let bits = match data_type {
+            DataType::F32 => u32::from_bytes(bytes, endianness) as i128,
+            DataType::F64 => u64::from_bytes(bytes, endianness) as i128,
+            _ => data_type.decode_int(bytes, endianness),
+        };
+        let digits = size * 2;
+        let sign = if bits < 0 { "-" } else { "" };
+        match self.radix {
+            Radix::Decimal => match data_type {
+                DataType::F32 => format_scientific_unicode(f32::from_bytes(bytes, endianness), 6),
+                DataType::F64 => format_scientific_unicode(f64::from_bytes(bytes, endianness), 14),
+                _ => bits.to_string(),
+            },
+            Radix::HexaDecimal => format!("{sign}0x{:0digits$X}", bits.unsigned_abs()),
+            Radix::Binary => {
+                let bin = format!("{:0width$b}", bits.unsigned_abs(), width = size * 8);
+                let groups: Vec<&str> = bin
+                    .as_bytes()
+                    .chunks(8)
+                    .map(|g| std::str::from_utf8(g).unwrap())
+                    .collect();
+                format!("{sign}{}", groups.join(" "))
+            }
+        }
+    }
+}
+
+impl Widget for Inspector<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let b = Block::default()
+            .title(format!(" Inspector @ 0x{:08X} ", self.offset))
+            .title_bottom(Line::from(format!(" {} ", self.radix)).right_aligned())
+            .border_style(Style::default().fg(Color::Cyan))
+            .border_type(BorderType::Rounded)
+            .borders(Borders::ALL);
+
+        let name_style = Style::default().fg(Color::LightCyan).bold();
+        let endian_style = Style::default().fg(Color::DarkGray);
+        let value_style = Style::default().fg(Color::Yellow);
+        let mut lines = Vec::new();
+        for data_type in DataType::ALL {
+            if data_type.size() == 1 {
+                lines.push(Line::from(vec![
+                    Span::styled(format!(" {:<4}", data_type.to_string()), name_style),
+                    Span::raw("    "),
+                    Span::styled(self.format(data_type, &Endianness::Little), value_style),
+                ]));
+                continue;
+            }
+            for (i, endianness) in [Endianness::Little, Endianness::Big].iter().enumerate() {
+                let name = if i == 0 {
+                    format!(" {:<4}", data_type.to_string())
+                } else {
+                    String::from("     ")
+                };
+                let tag = match endianness {
+                    Endianness::Little => "LE  ",
+                    Endianness::Big => "BE  ",
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(name, name_style),
+                    Span::styled(tag, endian_style),
+                    Span::styled(self.format(data_type, endianness), value_style),
+                ]));
+            }
+        }
+        Paragraph::new(lines).block(b).render(area, buf);
+    }
+}
+
+impl fmt::Display for Radix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Radix::Decimal => write!(f, "Decimal"),
+            Radix::HexaDecimal => write!(f, "HexaDecimal"),
+            Radix::Binary => write!(f, "Binary"),
+        }
+    }
+}
@@ -1,32 +1,60 @@
-use super::utils::last_n_components;
+use super::utils::{last_n_components, parse_int, parse_offset};
+use clipboard::{Copied, CopyFormat};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use edit_buffer::EditBuffer;
 use file_viewer::{FileViewer, FileViewerState};
+use inspector::{Inspector, Radix};
 use ratatui::{
     Frame,
     layout::{Constraint, Flex, Layout, Margin, Rect},
     style::{Color, Style, Stylize},
     text::{Line, Span},
-    widgets::{Block, BorderType, Borders, Paragraph, Widget},
+    widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, Widget},
 };
-use std::{fs, io::Result, path::PathBuf};
+use search::{Matches, SearchMessage, SearchQuery, SearchWorker};
+use std::{cmp::Ordering, fs, io::Result, ops::Range, path::PathBuf};
 #[cfg(debug_assertions)]
 use tracing::{info, instrument};
 
+mod address_map;
+mod byte_source;
+mod clipboard;
 mod common_dt;
+mod edit_buffer;
 mod file_viewer;
+mod inspector;
+mod search;
 
-use common_dt::{DataType, DisplayType, Endianness};
+pub use address_map::AddressMap;
+pub use byte_source::ByteSource;
+use byte_source::StdinStream;
+use common_dt::CharSet;
+pub use common_dt::{DataType, DisplayType, Endianness, FloatNotation, MAX_PRECISION};
+pub use file_viewer::{format_float, group_nibbles};
 
 #[derive(Debug, Default)]
 pub struct ViewerContainer {
-    file: PathBuf,
+    // None when the data was read from stdin
+    file: Option<PathBuf>,
     action_mode: ActionMode,
     file_viewer: FileViewer,
     file_viewer_state: FileViewerState,
     data_type: DataType,
     display_type: DisplayType,
     endianness: Endianness,
-    // search_field: String,
+    search_field: String,
+    goto_field: String,
+    value_field: String,
+    command_field: String,
+    insert_mode: bool,
+    prompt_error: Option<String>,
+    search_worker: Option<SearchWorker>,
+    show_inspector: bool,
+    inspector_radix: Radix,
+    message: Option<String>,
+    follow: bool,
+    // Still reading piped data, which grows the buffer like following a file
+    stdin: Option<StdinStream>,
 }
 
 pub enum ViewerContainerEvent {
@@ -40,7 +68,34 @@ pub enum ActionMode {
     #[default]
     Normal,
     SelectDataType(Option<KeyCode>),
-    // EditSearch,
+    EditSearch,
+    SearchResults,
+    Goto,
+    // Anchor of the selection, the other end follows the cursor
+    Visual(usize),
+    CopyAs(usize),
+    // Index of the next hex digit typed over the cell, most significant first
+    Edit(usize),
+    EditValue,
+    Fill(usize),
+    Command,
+    ConfirmQuit(Exit),
+}
+
+// Where the viewer goes once unsaved changes are saved or discarded
+#[derive(Debug, Clone)]
+pub enum Exit {
+    Quit,
+    SelectFile(PathBuf),
+}
+
+impl Exit {
+    fn event(self) -> ViewerContainerEvent {
+        match self {
+            Exit::Quit => ViewerContainerEvent::Quit,
+            Exit::SelectFile(dir) => ViewerContainerEvent::SelectFile(dir),
+        }
+    }
 }
 
 fn render_button(name: String, btn_color: Color, text_color: Color) -> impl Widget {
@@ -48,24 +103,234 @@ fn render_button(name: String, btn_color: Color, text_color: Color) -> impl Widg
 }
 
 impl ViewerContainer {
-    pub fn with_file(mut self, file: PathBuf) -> Self {
-        self.file = file;
+    pub fn with_file(mut self, file: PathBuf) -> Result<Self> {
+        let source = ByteSource::open(&file)?;
+
+        #[cfg(debug_assertions)]
+        info!("Content len: {}", source.len());
+
+        self.file_viewer.set_buffer(EditBuffer::new(source));
+        self.file = Some(file);
+        Ok(self)
+    }
+
+    pub fn with_stdin(mut self) -> Result<Self> {
+        self.stdin = Some(StdinStream::spawn()?);
+
+        #[cfg(debug_assertions)]
+        info!("Reading stdin");
+
+        self.file_viewer
+            .set_buffer(EditBuffer::new(ByteSource::default()));
+        self.file = None;
+        Ok(self)
+    }
+
+    pub fn with_data_type(mut self, data_type: DataType) -> Self {
+        self.data_type = data_type;
+        self.file_viewer.set_data_type(data_type);
+        self
+    }
+
+    pub fn with_display_type(mut self, display_type: DisplayType) -> Self {
+        self.display_type = display_type;
+        self.file_viewer.set_display_type(display_type);
+        self
+    }
+
+    pub fn with_endianness(mut self, endianness: Endianness) -> Self {
+        self.endianness = endianness;
+        self.file_viewer.set_endianness(endianness);
+        self
+    }
+
+    pub fn with_float_notation(mut self, float_notation: FloatNotation) -> Self {
+        self.file_viewer.set_float_notation(float_notation);
+        self
+    }
+
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.file_viewer.set_precision(Some(precision));
+        self
+    }
+
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        self.file_viewer_state.goto_offset(offset);
+        self
+    }
+
+    pub fn with_cols(mut self, cols: usize) -> Self {
+        self.file_viewer_state.set_cols(Some(cols));
+        self
+    }
+
+    pub fn with_address_map(mut self, address_map: AddressMap) -> Self {
+        self.file_viewer.set_address_map(address_map);
+        self
+    }
+
+    pub fn with_follow(mut self) -> Self {
+        self.toggle_follow();
         self
     }
 
     pub fn handle_key(&mut self, key: KeyEvent) -> ViewerContainerEvent {
-        match self.action_mode {
+        self.message = None;
+        let event = match self.action_mode {
             ActionMode::Normal => self.handle_normal_keys(key),
             ActionMode::SelectDataType(_) => self.handle_dt_keys(key),
+            ActionMode::EditSearch => self.handle_search_keys(key),
+            ActionMode::SearchResults => self.handle_results_keys(key),
+            ActionMode::Goto => self.handle_goto_keys(key),
+            ActionMode::Visual(anchor) => self.handle_visual_keys(anchor, key),
+            ActionMode::CopyAs(anchor) => self.handle_copy_keys(anchor, key),
+            ActionMode::Edit(digit) => self.handle_edit_keys(digit, key),
+            ActionMode::EditValue => self.handle_value_keys(key),
+            ActionMode::Fill(anchor) => self.handle_fill_keys(anchor, key),
+            ActionMode::Command => self.handle_command_keys(key),
+            ActionMode::ConfirmQuit(ref exit) => {
+                let exit = exit.clone();
+                self.handle_quit_keys(exit, key)
+            }
+        };
+        let inserting = matches!(
+            self.action_mode,
+            ActionMode::Edit(_) | ActionMode::EditValue
+        );
+        self.file_viewer_state
+            .set_past_end(inserting && self.insert_mode);
+        event
+    }
+
+    // Collects results from background work, called on every iteration of the event loop
+    pub fn tick(&mut self) {
+        if self.follow {
+            self.poll_file();
+        }
+        if self.stdin.is_some() {
+            self.poll_stdin();
+        }
+        let Some(worker) = &mut self.search_worker else {
+            return;
+        };
+        while let Some(msg) = worker.poll() {
+            match msg {
+                SearchMessage::Found(found) => {
+                    let matches = self.file_viewer.matches_mut();
+                    let first = matches.is_empty();
+                    matches.extend(found);
+                    if first && let Some(offset) = matches.next(self.file_viewer_state.cursor()) {
+                        self.file_viewer_state.goto_offset(offset);
+                    }
+                }
+                SearchMessage::Progress(_) => {}
+                SearchMessage::Done => {
+                    #[cfg(debug_assertions)]
+                    info!("Search done: {} matches", self.file_viewer.matches().len());
+
+                    self.search_worker = None;
+                    return;
+                }
+            }
+        }
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.search_worker.is_some() || self.follow || self.stdin.is_some()
+    }
+
+    fn toggle_follow(&mut self) {
+        if self.follow {
+            self.follow = false;
+            self.message = Some(String::from("Stopped following"));
+            return;
+        }
+        if self.file.is_none() || self.stdin.is_some() {
+            self.message = Some(String::from("Only files can be followed"));
+            return;
+        }
+        self.follow = true;
+        self.poll_file();
+        self.file_viewer_state.set_len(self.file_viewer.len());
+        self.file_viewer_state.goto_bottom();
+    }
+
+    // Picks up bytes appended to the file since the last tick, like `tail -f`. The view sticks to
+    // the bottom while the cursor is on the last cell.
+    fn poll_file(&mut self) {
+        let Some(file) = &self.file else {
+            return;
+        };
+        let Ok(metadata) = fs::metadata(file) else {
+            return;
+        };
+        let len = metadata.len() as usize;
+        match len.cmp(&self.file_viewer.buffer().source_len()) {
+            Ordering::Equal => {}
+            Ordering::Less => {
+                self.follow = false;
+                self.message = Some(String::from("File shrank, stopped following"));
+            }
+            Ordering::Greater => match ByteSource::open(file) {
+                Ok(source) => self.append_source(source),
+                Err(err) => {
+                    self.follow = false;
+                    self.message = Some(format!("Follow failed: {err}"));
+                }
+            },
+        }
+    }
+
+    // Picks up what arrived on stdin since the last tick, the last bytes are taken once it closes
+    fn poll_stdin(&mut self) {
+        let Some(stream) = &mut self.stdin else {
+            return;
+        };
+        let finished = stream.finished();
+        let grown = stream
+            .len()
+            .is_ok_and(|len| len > self.file_viewer.buffer().source_len());
+        match grown.then(|| stream.source()) {
+            Some(Ok(source)) => self.append_source(source),
+            Some(Err(err)) => {
+                self.stdin = None;
+                self.message = Some(format!("Reading stdin failed: {err}"));
+                return;
+            }
+            None => {}
+        }
+        match finished {
+            Some(Ok(_)) => self.stdin = None,
+            Some(Err(err)) => {
+                self.stdin = None;
+                self.message = Some(format!("Reading stdin failed: {err}"));
+            }
+            None => {}
+        }
+    }
+
+    fn append_source(&mut self, source: ByteSource) {
+        let at_bottom = self.file_viewer_state.at_bottom();
+        self.file_viewer.buffer_mut().append_source(source);
+        self.file_viewer_state.set_len(self.file_viewer.len());
+        if at_bottom {
+            self.file_viewer_state.goto_bottom();
         }
     }
 
     fn handle_normal_keys(&mut self, key: KeyEvent) -> ViewerContainerEvent {
         match (key.modifiers, key.code) {
+            (_, KeyCode::Esc) if self.search_worker.is_some() => {
+                if let Some(worker) = self.search_worker.take() {
+                    worker.cancel();
+                }
+            }
             (_, KeyCode::Esc | KeyCode::Char('q'))
             | (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C')) => {
-                return ViewerContainerEvent::Quit;
+                return self.exit(Exit::Quit);
             }
+            (KeyModifiers::CONTROL, KeyCode::Char('r')) => self.redo(),
+            (_, KeyCode::Char('u')) => self.undo(),
             (_, KeyCode::Char('d')) => {
                 self.display_type = DisplayType::Decimal;
                 self.file_viewer.set_display_type(DisplayType::Decimal);
@@ -74,20 +339,78 @@ impl ViewerContainer {
                 self.display_type = DisplayType::HexaDecimal;
                 self.file_viewer.set_display_type(DisplayType::HexaDecimal);
             }
-            (KeyModifiers::SHIFT, KeyCode::Char('l')) => {
+            (_, KeyCode::Char('o')) => {
+                self.display_type = DisplayType::Octal;
+                self.file_viewer.set_display_type(DisplayType::Octal);
+            }
+            (_, KeyCode::Char('b')) => {
+                self.display_type = DisplayType::Binary;
+                self.file_viewer.set_display_type(DisplayType::Binary);
+            }
+            (_, KeyCode::Char('L')) => {
                 self.endianness = Endianness::Little;
                 self.file_viewer.set_endianness(Endianness::Little);
             }
-            (KeyModifiers::SHIFT, KeyCode::Char('b')) => {
+            (_, KeyCode::Char('B')) => {
                 self.endianness = Endianness::Big;
                 self.file_viewer.set_endianness(Endianness::Big);
             }
+            (_, KeyCode::Char('a')) => {
+                let charset = match self.file_viewer.charset() {
+                    None => Some(CharSet::Ascii),
+                    Some(CharSet::Ascii) => Some(CharSet::Latin1),
+                    Some(CharSet::Latin1) => Some(CharSet::Utf8),
+                    Some(CharSet::Utf8) => None,
+                };
+                self.file_viewer.set_charset(charset);
+            }
+            (_, KeyCode::Char('i')) => self.show_inspector = !self.show_inspector,
+            (_, KeyCode::Char('r')) => self.inspector_radix = self.inspector_radix.next(),
+            (_, KeyCode::Char('>')) => self.file_viewer.shift_forward(),
+            (_, KeyCode::Char('<')) => self.file_viewer.shift_backward(),
             (KeyModifiers::CONTROL, KeyCode::Char('t')) => {
                 self.action_mode = ActionMode::SelectDataType(None)
             }
+            (_, KeyCode::Char('g')) => self.action_mode = ActionMode::Goto,
+            (_, KeyCode::Char('/')) => {
+                self.search_field.clear();
+                self.action_mode = ActionMode::EditSearch;
+            }
+            (_, KeyCode::Char('m')) if !self.file_viewer.matches().is_empty() => {
+                self.action_mode = ActionMode::SearchResults
+            }
+            (_, KeyCode::Char('n')) => {
+                let offset = self.file_viewer_state.cursor();
+                if let Some(offset) = self.file_viewer.matches_mut().next(offset) {
+                    self.file_viewer_state.goto_offset(offset);
+                }
+            }
+            (_, KeyCode::Char('N')) => {
+                let offset = self.file_viewer_state.cursor();
+                if let Some(offset) = self.file_viewer.matches_mut().prev(offset) {
+                    self.file_viewer_state.goto_offset(offset);
+                }
+            }
             (KeyModifiers::CONTROL, KeyCode::Char('f')) => {
-                return ViewerContainerEvent::SelectFile(self.file.parent().unwrap().to_owned());
+                let dir = match &self.file {
+                    Some(file) => file.parent().unwrap().to_owned(),
+                    None => PathBuf::from("."),
+                };
+                return self.exit(Exit::SelectFile(dir));
+            }
+            (_, KeyCode::Char('F')) => self.toggle_follow(),
+            (_, KeyCode::Char('e')) => self.action_mode = ActionMode::Edit(0),
+            (_, KeyCode::Char(':')) => self.action_mode = ActionMode::Command,
+            (_, KeyCode::Char('v')) => {
+                self.action_mode = ActionMode::Visual(self.file_viewer_state.cursor())
             }
+            _ => self.handle_motion_keys(key),
+        }
+        ViewerContainerEvent::Poll
+    }
+
+    fn handle_motion_keys(&mut self, key: KeyEvent) {
+        match (key.modifiers, key.code) {
             (_, KeyCode::Char('j') | KeyCode::Down) => self.file_viewer_state.move_down(),
             (_, KeyCode::Char('k') | KeyCode::Up) => self.file_viewer_state.move_up(),
             (_, KeyCode::Char('h') | KeyCode::Left) => self.file_viewer_state.move_left(),
@@ -100,9 +423,54 @@ impl ViewerContainer {
             (_, KeyCode::PageDown) => self.file_viewer_state.scroll_down(),
             _ => {}
         }
+    }
+
+    fn handle_visual_keys(&mut self, anchor: usize, key: KeyEvent) -> ViewerContainerEvent {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('v') => self.action_mode = ActionMode::Normal,
+            KeyCode::Char('y') => self.action_mode = ActionMode::CopyAs(anchor),
+            KeyCode::Char('f') => self.action_mode = ActionMode::Fill(anchor),
+            KeyCode::Char('d') | KeyCode::Delete => {
+                let range = self.selection(anchor);
+                self.file_viewer_state.goto_offset(range.start);
+                let buffer = self.file_viewer.buffer_mut();
+                buffer.begin(range.start);
+                buffer.delete(range.start, range.len());
+                self.length_changed();
+                self.action_mode = ActionMode::Normal;
+            }
+            _ => self.handle_motion_keys(key),
+        }
+        ViewerContainerEvent::Poll
+    }
+
+    fn handle_copy_keys(&mut self, anchor: usize, key: KeyEvent) -> ViewerContainerEvent {
+        let KeyCode::Char(c) = key.code else {
+            self.action_mode = ActionMode::Visual(anchor);
+            return ViewerContainerEvent::Poll;
+        };
+        let Some(format) = CopyFormat::ALL.into_iter().find(|f| f.key() == c) else {
+            return ViewerContainerEvent::Poll;
+        };
+        let range = self.selection(anchor);
+        let bytes = self.file_viewer.buffer().read(range.start, range.len());
+        self.message = Some(match clipboard::copy(format, &bytes) {
+            Ok(Copied::Clipboard) => format!("Copied {} bytes as {}", bytes.len(), format.name()),
+            Ok(Copied::File(path)) => format!("Saved {} bytes to {}", bytes.len(), path.display()),
+            Err(err) => format!("Copy failed: {err}"),
+        });
+        self.action_mode = ActionMode::Normal;
         ViewerContainerEvent::Poll
     }
 
+    // Both ends are inclusive of the whole element under them
+    fn selection(&self, anchor: usize) -> Range<usize> {
+        let cursor = self.file_viewer_state.cursor();
+        let size = self.data_type.size();
+        let end = (anchor.max(cursor) + size).min(self.file_viewer.len());
+        anchor.min(cursor)..end
+    }
+
     fn handle_dt_keys(&mut self, key: KeyEvent) -> ViewerContainerEvent {
         use KeyCode::Char;
         if let ActionMode::SelectDataType(Some(x)) = self.action_mode {
@@ -161,19 +529,460 @@ impl ViewerContainer {
         ViewerContainerEvent::Poll
     }
 
+    fn handle_search_keys(&mut self, key: KeyEvent) -> ViewerContainerEvent {
+        match key.code {
+            KeyCode::Esc => {
+                self.search_field.clear();
+                self.prompt_error = None;
+                self.action_mode = ActionMode::Normal;
+            }
+            KeyCode::Enter => {
+                match SearchQuery::parse(&self.search_field, self.data_type, self.endianness) {
+                    Ok(query) => {
+                        #[cfg(debug_assertions)]
+                        info!("Search {query:?}");
+
+                        if let Some(worker) = self.search_worker.take() {
+                            worker.cancel();
+                        }
+                        let source = self.file_viewer.buffer().contents();
+                        self.search_worker = Some(SearchWorker::spawn(query, source));
+                        self.file_viewer.set_matches(Matches::default());
+                        self.action_mode = ActionMode::Normal;
+                    }
+                    Err(err) => self.prompt_error = Some(err),
+                }
+            }
+            KeyCode::Backspace => {
+                self.search_field.pop();
+                self.prompt_error = None;
+            }
+            KeyCode::Char(c) => {
+                self.search_field.push(c);
+                self.prompt_error = None;
+            }
+            _ => {}
+        }
+        ViewerContainerEvent::Poll
+    }
+
+    fn handle_results_keys(&mut self, key: KeyEvent) -> ViewerContainerEvent {
+        let matches = self.file_viewer.matches_mut();
+        let selected = matches.current().unwrap_or(0);
+        let target = match key.code {
+            KeyCode::Char('j') | KeyCode::Down => matches.select(selected + 1),
+            KeyCode::Char('k') | KeyCode::Up => matches.select(selected.saturating_sub(1)),
+            KeyCode::PageDown => matches.select((selected + 10).min(matches.len() - 1)),
+            KeyCode::PageUp => matches.select(selected.saturating_sub(10)),
+            KeyCode::Enter | KeyCode::Esc | KeyCode::Char('m') => {
+                self.action_mode = ActionMode::Normal;
+                None
+            }
+            _ => None,
+        };
+        if let Some(offset) = target {
+            self.file_viewer_state.goto_offset(offset);
+        }
+        ViewerContainerEvent::Poll
+    }
+
+    // A leading `@` goes to an address in the loaded image instead of a file offset
+    fn goto_target(&self) -> std::result::Result<usize, String> {
+        let cursor = self.file_viewer_state.cursor();
+        let Some(address) = self.goto_field.trim().strip_prefix('@') else {
+            return parse_offset(&self.goto_field, cursor);
+        };
+        let map = self.file_viewer.address_map();
+        let current = map.to_address(cursor).unwrap_or_default();
+        let address = parse_offset(address, current)?;
+        map.to_offset(address)
+            .ok_or_else(|| format!("0x{address:X} is not mapped"))
+    }
+
+    fn handle_goto_keys(&mut self, key: KeyEvent) -> ViewerContainerEvent {
+        match key.code {
+            KeyCode::Esc => {
+                self.goto_field.clear();
+                self.prompt_error = None;
+                self.action_mode = ActionMode::Normal;
+            }
+            KeyCode::Enter => match self.goto_target() {
+                Ok(offset) if offset < self.file_viewer.len() => {
+                    self.file_viewer_state.goto_offset(offset);
+                    self.goto_field.clear();
+                    self.action_mode = ActionMode::Normal;
+                }
+                Ok(_) => self.prompt_error = Some(String::from("beyond end of file")),
+                Err(err) => self.prompt_error = Some(err),
+            },
+            KeyCode::Backspace => {
+                self.goto_field.pop();
+                self.prompt_error = None;
+            }
+            KeyCode::Char(c) => {
+                self.goto_field.push(c);
+                self.prompt_error = None;
+            }
+            _ => {}
+        }
+        ViewerContainerEvent::Poll
+    }
+
+    fn handle_edit_keys(&mut self, digit: usize, key: KeyEvent) -> ViewerContainerEvent {
+        match key.code {
+            KeyCode::Esc => self.action_mode = ActionMode::Normal,
+            KeyCode::Char('=') => self.action_mode = ActionMode::EditValue,
+            KeyCode::Insert | KeyCode::Tab => {
+                self.insert_mode = !self.insert_mode;
+                self.action_mode = ActionMode::Edit(0);
+            }
+            KeyCode::Char('u') => self.undo(),
+            KeyCode::Char('r') if key.modifiers == KeyModifiers::CONTROL => self.redo(),
+            KeyCode::Delete => {
+                let cursor = self.file_viewer_state.cursor();
+                let buffer = self.file_viewer.buffer_mut();
+                buffer.begin(cursor);
+                buffer.delete(cursor, self.data_type.size());
+                self.length_changed();
+                self.action_mode = ActionMode::Edit(0);
+            }
+            KeyCode::Backspace => {
+                let size = self.data_type.size();
+                let cursor = self.file_viewer_state.cursor();
+                if let Some(offset) = cursor.checked_sub(size) {
+                    let buffer = self.file_viewer.buffer_mut();
+                    buffer.begin(cursor);
+                    buffer.delete(offset, size);
+                    self.file_viewer_state.goto_offset(offset);
+                    self.length_changed();
+                }
+                self.action_mode = ActionMode::Edit(0);
+            }
+            KeyCode::Char(c) if c.is_ascii_hexdigit() => {
+                let size = self.data_type.size();
+                let cursor = self.file_viewer_state.cursor();
+                // All digits typed over one cell are undone together
+                if digit == 0 {
+                    self.file_viewer.buffer_mut().begin(cursor);
+                }
+                // A new zeroed cell is inserted with the first digit, the others overwrite it
+                if self.insert_mode && digit == 0 {
+                    let zeros = vec![0; size];
+                    self.file_viewer.buffer_mut().insert(cursor, &zeros);
+                    self.length_changed();
+                }
+                let buffer = self.file_viewer.buffer();
+                if cursor + size > buffer.len() {
+                    self.message = Some(String::from("Cell extends past the end of the file"));
+                    return ViewerContainerEvent::Poll;
+                }
+                let bits = self
+                    .data_type
+                    .decode_bits(&buffer.read(cursor, size), &self.endianness);
+                let shift = (size * 2 - 1 - digit) * 4;
+                let value = c.to_digit(16).unwrap() as u64;
+                let bits = (bits & !(0xF << shift)) | (value << shift);
+                let bytes = self.data_type.encode_bits(bits, &self.endianness);
+                self.file_viewer.buffer_mut().overwrite(cursor, &bytes);
+                if digit + 1 < size * 2 {
+                    self.action_mode = ActionMode::Edit(digit + 1);
+                } else {
+                    self.file_viewer_state.move_right();
+                    self.action_mode = ActionMode::Edit(0);
+                }
+            }
+            _ => {
+                self.handle_motion_keys(key);
+                self.action_mode = ActionMode::Edit(0);
+            }
+        }
+        ViewerContainerEvent::Poll
+    }
+
+    fn handle_value_keys(&mut self, key: KeyEvent) -> ViewerContainerEvent {
+        match key.code {
+            KeyCode::Esc => {
+                self.value_field.clear();
+                self.prompt_error = None;
+                self.action_mode = ActionMode::Edit(0);
+            }
+            KeyCode::Enter => match self.write_value() {
+                // Stays open so consecutive cells can be filled in one go
+                Ok(()) => {
+                    self.value_field.clear();
+                    self.file_viewer_state.move_right();
+                }
+                Err(err) => self.prompt_error = Some(err),
+            },
+            KeyCode::Backspace => {
+                self.value_field.pop();
+                self.prompt_error = None;
+            }
+            KeyCode::Char(c) => {
+                self.value_field.push(c);
+                self.prompt_error = None;
+            }
+            _ => {}
+        }
+        ViewerContainerEvent::Poll
+    }
+
+    // Encodes the typed value in the current data type and byte order over the cursor cell
+    fn write_value(&mut self) -> std::result::Result<(), String> {
+        let bytes = self.parse_value()?;
+        let cursor = self.file_viewer_state.cursor();
+        if !self.insert_mode && cursor + bytes.len() > self.file_viewer.len() {
+            return Err(String::from("cell extends past the end of the file"));
+        }
+        let buffer = self.file_viewer.buffer_mut();
+        buffer.begin(cursor);
+        if self.insert_mode {
+            buffer.insert(cursor, &bytes);
+            self.length_changed();
+        } else {
+            buffer.overwrite(cursor, &bytes);
+        }
+        Ok(())
+    }
+
+    fn parse_value(&self) -> std::result::Result<Vec<u8>, String> {
+        let bytes = match self.data_type.int_range() {
+            Some((min, max)) => {
+                let value = parse_int(&self.value_field)?;
+                if value < min || value > max {
+                    return Err(format!("out of range for {}", self.data_type));
+                }
+                self.data_type.encode_int(value, &self.endianness)
+            }
+            None => {
+                let input = self.value_field.trim();
+                let value: f64 = input
+                    .parse()
+                    .map_err(|_| format!("invalid number '{input}'"))?;
+                self.data_type.encode_float(value, &self.endianness)
+            }
+        };
+        Ok(bytes)
+    }
+
+    fn handle_fill_keys(&mut self, anchor: usize, key: KeyEvent) -> ViewerContainerEvent {
+        match key.code {
+            KeyCode::Esc => {
+                self.value_field.clear();
+                self.prompt_error = None;
+                self.action_mode = ActionMode::Visual(anchor);
+            }
+            // Repeats the value over the whole selection
+            KeyCode::Enter => match self.parse_value() {
+                Ok(pattern) => {
+                    let range = self.selection(anchor);
+                    let bytes: Vec<u8> = pattern.into_iter().cycle().take(range.len()).collect();
+                    let buffer = self.file_viewer.buffer_mut();
+                    buffer.begin(range.start);
+                    buffer.overwrite(range.start, &bytes);
+                    self.message = Some(format!("Filled {} bytes", bytes.len()));
+                    self.value_field.clear();
+                    self.action_mode = ActionMode::Normal;
+                }
+                Err(err) => self.prompt_error = Some(err),
+            },
+            KeyCode::Backspace => {
+                self.value_field.pop();
+                self.prompt_error = None;
+            }
+            KeyCode::Char(c) => {
+                self.value_field.push(c);
+                self.prompt_error = None;
+            }
+            _ => {}
+        }
+        ViewerContainerEvent::Poll
+    }
+
+    fn undo(&mut self) {
+        match self.file_viewer.buffer_mut().undo() {
+            Some(cursor) => {
+                self.length_changed();
+                self.file_viewer_state.goto_offset(cursor);
+            }
+            None => self.message = Some(String::from("Already at oldest change")),
+        }
+    }
+
+    fn redo(&mut self) {
+        match self.file_viewer.buffer_mut().redo() {
+            Some(cursor) => {
+                self.length_changed();
+                self.file_viewer_state.goto_offset(cursor);
+            }
+            None => self.message = Some(String::from("Already at newest change")),
+        }
+    }
+
+    // Asks to save first when leaving would drop unsaved changes
+    fn exit(&mut self, exit: Exit) -> ViewerContainerEvent {
+        if self.file_viewer.buffer().is_dirty() {
+            self.action_mode = ActionMode::ConfirmQuit(exit);
+            return ViewerContainerEvent::Poll;
+        }
+        exit.event()
+    }
+
+    fn handle_quit_keys(&mut self, exit: Exit, key: KeyEvent) -> ViewerContainerEvent {
+        self.action_mode = ActionMode::Normal;
+        match key.code {
+            // Data from stdin has no file yet, the name is typed after `:w`
+            KeyCode::Char('y') if self.file.is_none() => {
+                self.command_field = match exit {
+                    Exit::Quit => String::from("wq "),
+                    Exit::SelectFile(_) => String::from("w "),
+                };
+                self.action_mode = ActionMode::Command;
+                ViewerContainerEvent::Poll
+            }
+            KeyCode::Char('y') if self.save(None) => exit.event(),
+            KeyCode::Char('n') => exit.event(),
+            _ => ViewerContainerEvent::Poll,
+        }
+    }
+
+    // Offsets found before an insert or delete no longer point at the matched bytes
+    fn length_changed(&mut self) {
+        self.file_viewer_state.set_len(self.file_viewer.len());
+        if let Some(worker) = self.search_worker.take() {
+            worker.cancel();
+        }
+        self.file_viewer.set_matches(Matches::default());
+    }
+
+    fn handle_command_keys(&mut self, key: KeyEvent) -> ViewerContainerEvent {
+        match key.code {
+            KeyCode::Esc => {
+                self.command_field.clear();
+                self.prompt_error = None;
+                self.action_mode = ActionMode::Normal;
+            }
+            KeyCode::Enter => {
+                let command = std::mem::take(&mut self.command_field);
+                self.action_mode = ActionMode::Normal;
+                return self.run_command(command.trim());
+            }
+            KeyCode::Backspace => {
+                self.command_field.pop();
+                self.prompt_error = None;
+            }
+            KeyCode::Char(c) => {
+                self.command_field.push(c);
+                self.prompt_error = None;
+            }
+            _ => {}
+        }
+        ViewerContainerEvent::Poll
+    }
+
+    fn run_command(&mut self, command: &str) -> ViewerContainerEvent {
+        // `:w FILE` writes to another file, which also names data read from stdin
+        let (name, arg) = match command.split_once(' ') {
+            Some((name, arg)) => (name, Some(arg.trim())),
+            None => (command, None),
+        };
+        match (name, arg) {
+            ("w", path) => {
+                self.save(path.map(PathBuf::from));
+            }
+            ("wq", path) => {
+                if self.save(path.map(PathBuf::from)) {
+                    return ViewerContainerEvent::Quit;
+                }
+            }
+            ("cols", Some("auto")) => self.file_viewer_state.set_cols(None),
+            // Capped like --cols
+            ("cols", Some(cols)) => match parse_int(cols).map(u16::try_from) {
+                Ok(Ok(cols)) if cols > 0 => self.file_viewer_state.set_cols(Some(cols as usize)),
+                Ok(_) => self.command_error(command, format!("expected 1 to {} columns", u16::MAX)),
+                Err(err) => self.command_error(command, err),
+            },
+            ("float", Some(notation)) => match notation.parse() {
+                Ok(notation) => self.file_viewer.set_float_notation(notation),
+                Err(err) => self.command_error(command, err),
+            },
+            ("prec", Some("auto")) => self.file_viewer.set_precision(None),
+            ("prec", Some(precision)) => match parse_int(precision).map(usize::try_from) {
+                Ok(Ok(precision)) if precision <= MAX_PRECISION => {
+                    self.file_viewer.set_precision(Some(precision))
+                }
+                Ok(_) => {
+                    self.command_error(command, format!("expected at most {MAX_PRECISION} digits"))
+                }
+                Err(err) => self.command_error(command, err),
+            },
+            ("base" | "map", Some("off")) => {
+                self.file_viewer.set_address_map(AddressMap::default());
+            }
+            ("base", Some(base)) => match parse_int(base).map(usize::try_from) {
+                Ok(Ok(base)) => self
+                    .file_viewer
+                    .set_address_map(AddressMap::with_base(base)),
+                Ok(Err(_)) => self.command_error(command, String::from("base is out of range")),
+                Err(err) => self.command_error(command, err),
+            },
+            ("map", Some(segments)) => match segments.parse() {
+                Ok(map) => self.file_viewer.set_address_map(map),
+                Err(err) => self.command_error(command, err),
+            },
+            ("q", None) if self.file_viewer.buffer().is_dirty() => {
+                self.prompt_error = Some(String::from("unsaved changes, :wq saves, :q! discards"));
+                self.action_mode = ActionMode::Command;
+            }
+            ("q" | "q!", None) => return ViewerContainerEvent::Quit,
+            _ => self.command_error(command, String::from("unknown command")),
+        }
+        ViewerContainerEvent::Poll
+    }
+
+    // Reopens the prompt with the command so it can be fixed
+    fn command_error(&mut self, command: &str, err: String) {
+        self.command_field = command.to_string();
+        self.prompt_error = Some(err);
+        self.action_mode = ActionMode::Command;
+    }
+
+    fn save(&mut self, path: Option<PathBuf>) -> bool {
+        let Some(path) = path.or_else(|| self.file.clone()) else {
+            self.prompt_error = Some(String::from("no file name, use :w FILE"));
+            self.action_mode = ActionMode::Command;
+            return false;
+        };
+        // Stdin keeps growing the buffer, so it stays the source until it is closed
+        let buffer = self.file_viewer.buffer_mut();
+        let result = match self.stdin.is_some() {
+            true => buffer.save_keeping_source(&path).map(|_| ()),
+            false => buffer.save(&path),
+        };
+        self.message = Some(match &result {
+            Ok(()) => format!("Wrote {} bytes", self.file_viewer.len()),
+            Err(err) => format!("Save failed: {err}"),
+        });
+        if result.is_ok() {
+            self.file = Some(path);
+        }
+        result.is_ok()
+    }
+
     #[cfg_attr(debug_assertions, instrument(skip_all, name = "Viewer::render_viewer"))]
     pub fn render_viewer(&mut self, frame: &mut Frame) -> Result<()> {
         let page_layout = Layout::vertical([
             Constraint::Length(3),
             Constraint::Length(3),
             Constraint::Fill(1),
+            Constraint::Length(1),
         ])
-        .areas::<3>(frame.area());
+        .areas::<4>(frame.area());
 
-        let layout = Layout::horizontal([Constraint::Length(70), Constraint::Fill(1)])
+        let top_layout = Layout::horizontal([Constraint::Length(70), Constraint::Fill(1)])
             .areas::<2>(page_layout[0]);
-        self.render_file_name(layout[0], frame);
-        self.render_search_bar(layout[1], frame);
+        self.render_file_name(top_layout[0], frame);
+        self.render_search_bar(top_layout[1], frame);
 
         let layout = Layout::horizontal([
             Constraint::Length(88),
@@ -187,31 +996,151 @@ impl ViewerContainer {
         self.render_display_buttons(layout[1], frame);
         self.render_endianness_buttons(layout[2], frame);
 
-        let content = fs::read(&self.file)?;
+        let mut viewer_area = page_layout[2];
+        if self.show_inspector {
+            let width = Inspector::width(self.inspector_radix);
+            let [viewer, inspector] =
+                Layout::horizontal([Constraint::Fill(1), Constraint::Length(width)])
+                    .areas::<2>(page_layout[2]);
+            viewer_area = viewer;
+            let cursor = self.file_viewer_state.cursor();
+            let bytes = self.file_viewer.buffer().read(cursor, 8);
+            frame.render_widget(
+                Inspector::new(&bytes, cursor, self.inspector_radix),
+                inspector,
+            );
+        }
+        let selection = match self.action_mode {
+            ActionMode::Visual(anchor) | ActionMode::CopyAs(anchor) => Some(self.selection(anchor)),
+            _ => None,
+        };
+        self.file_viewer.set_selection(selection);
+        frame.render_stateful_widget(&self.file_viewer, viewer_area, &mut self.file_viewer_state);
 
-        #[cfg(debug_assertions)]
-        info!("Content len: {}", content.len());
+        self.render_status_line(page_layout[3], frame);
 
-        self.file_viewer.set_content(content);
-        frame.render_stateful_widget(
-            &self.file_viewer,
-            page_layout[2],
-            &mut self.file_viewer_state,
-        );
+        match self.action_mode {
+            ActionMode::SearchResults => self.render_search_results(top_layout[1], frame),
+            ActionMode::CopyAs(_) => self.render_copy_menu(viewer_area, frame),
+            _ => {}
+        }
         Ok(())
     }
 
+    fn render_copy_menu(&self, viewer: Rect, frame: &mut Frame) {
+        let lines: Vec<Line> = CopyFormat::ALL
+            .iter()
+            .map(|format| {
+                Line::from(vec![
+                    Span::styled(
+                        format!(" {} ", format.key()),
+                        Style::default().fg(Color::LightCyan).bold(),
+                    ),
+                    Span::styled(format.name(), Style::default().fg(Color::Gray)),
+                ])
+            })
+            .collect();
+        let [area] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)])
+            .flex(Flex::Center)
+            .areas(viewer);
+        let [area] = Layout::horizontal([Constraint::Length(24)])
+            .flex(Flex::Center)
+            .areas(area);
+        let b = Block::default()
+            .title(" Copy as ")
+            .border_style(Style::default().fg(Color::Cyan))
+            .border_type(BorderType::Rounded)
+            .borders(Borders::ALL);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).block(b), area);
+    }
+
+    fn render_status_line(&self, rect: Rect, frame: &mut Frame) {
+        let cursor = self.file_viewer_state.cursor();
+        let len = self.file_viewer.len();
+        let percent = (cursor + 1) * 100 / len.max(1);
+        let mut status = Line::from(vec![
+            Span::styled(" Offset ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                format!("0x{cursor:08X}"),
+                Style::default().fg(Color::LightYellow).bold(),
+            ),
+            Span::styled(format!(" ({cursor})"), Style::default().fg(Color::Gray)),
+            Span::styled(
+                format!("  {len} bytes  {percent:>3}% "),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]);
+        let map = self.file_viewer.address_map();
+        if !map.is_empty() {
+            let address = match map.to_address(cursor) {
+                Some(address) => format!("0x{address:08X}"),
+                None => String::from("unmapped"),
+            };
+            status.push_span(Span::styled(" Address ", Style::default().fg(Color::Gray)));
+            status.push_span(Span::styled(
+                format!("{address} "),
+                Style::default().fg(Color::LightCyan).bold(),
+            ));
+        }
+        if let ActionMode::Visual(anchor) | ActionMode::CopyAs(anchor) = self.action_mode {
+            let selection = self.selection(anchor);
+            status.push_span(Span::styled(
+                format!(" VISUAL {} bytes ", selection.len()),
+                Style::default().fg(Color::Black).bg(Color::LightCyan),
+            ));
+        }
+        if let ActionMode::Edit(_) | ActionMode::EditValue = self.action_mode {
+            let mode = match self.insert_mode {
+                true => " INSERT ",
+                false => " EDIT ",
+            };
+            status.push_span(Span::styled(
+                mode,
+                Style::default().fg(Color::Black).bg(Color::LightRed),
+            ));
+        }
+        if self.follow {
+            status.push_span(Span::styled(
+                " FOLLOW ",
+                Style::default().fg(Color::Black).bg(Color::LightGreen),
+            ));
+        }
+        if self.stdin.is_some() {
+            status.push_span(Span::styled(
+                " STDIN ",
+                Style::default().fg(Color::Black).bg(Color::LightGreen),
+            ));
+        }
+        if let Some(message) = &self.message {
+            status.push_span(Span::styled(
+                format!("  {message}"),
+                Style::default().fg(Color::LightGreen),
+            ));
+        }
+        frame.render_widget(status, rect);
+    }
+
     fn render_file_name(&mut self, rect: Rect, frame: &mut Frame) {
+        let mut title = Line::from(" File ");
+        if self.file_viewer.buffer().is_dirty() {
+            title.push_span(Span::styled(
+                "[+] ",
+                Style::default().fg(Color::LightRed).bold(),
+            ));
+        }
         let b = Block::default()
             .border_style(Style::default().fg(Color::Cyan))
             .border_type(BorderType::Rounded)
             .borders(Borders::ALL)
-            .title(Line::from(" File "));
+            .title(title);
 
         let fg = Color::LightYellow;
         let bg = Color::Blue;
         frame.render_widget(b, rect);
-        let (file_comp_n, file_name_3comp) = last_n_components(&self.file, 3);
+        let stdin = PathBuf::from("<stdin>");
+        let (file_comp_n, file_name_3comp) =
+            last_n_components(self.file.as_ref().unwrap_or(&stdin), 3);
         let mut file_name = Line::from(Span::styled(" ", Style::default().bg(bg)));
         if file_comp_n > 3 {
             file_name.push_span(Span::styled(
@@ -237,12 +1166,173 @@ impl ViewerContainer {
     }
 
     fn render_search_bar(&mut self, rect: Rect, frame: &mut Frame) {
+        match self.action_mode {
+            ActionMode::Goto => {
+                self.render_prompt(" Goto ", &self.goto_field, rect, frame);
+                return;
+            }
+            ActionMode::EditSearch => {
+                self.render_prompt(" Search ", &self.search_field, rect, frame);
+                return;
+            }
+            ActionMode::EditValue | ActionMode::Fill(_) => {
+                let endianness = match self.endianness {
+                    Endianness::Little => "LE",
+                    Endianness::Big => "BE",
+                };
+                let name = match self.action_mode {
+                    ActionMode::Fill(_) => "Fill",
+                    _ => "Value",
+                };
+                let title = format!(" {name} ({} {endianness}) ", self.data_type);
+                self.render_prompt(&title, &self.value_field, rect, frame);
+                return;
+            }
+            ActionMode::ConfirmQuit(ref exit) => {
+                let (title, leaving) = match exit {
+                    Exit::Quit => (" Quit ", "quitting"),
+                    Exit::SelectFile(_) => (" Open File ", "opening another file"),
+                };
+                let b = Block::default()
+                    .title(title)
+                    .border_style(Style::default().fg(Color::LightRed))
+                    .border_type(BorderType::Rounded)
+                    .borders(Borders::ALL);
+                let question = Line::from(vec![
+                    Span::styled(
+                        format!("Save changes before {leaving}? "),
+                        Style::default().fg(Color::Gray),
+                    ),
+                    Span::styled("y", Style::default().fg(Color::LightCyan).bold()),
+                    Span::styled("es / ", Style::default().fg(Color::Gray)),
+                    Span::styled("n", Style::default().fg(Color::LightCyan).bold()),
+                    Span::styled("o / any key to cancel", Style::default().fg(Color::Gray)),
+                ]);
+                frame.render_widget(b, rect);
+                frame.render_widget(
+                    question,
+                    rect.inner(Margin {
+                        horizontal: 2,
+                        vertical: 1,
+                    }),
+                );
+                return;
+            }
+            ActionMode::Command => {
+                self.render_prompt(" Command ", &self.command_field, rect, frame);
+                return;
+            }
+            _ => {}
+        }
         let b = Block::default()
             .title(" Search ")
             .border_style(Style::default().fg(Color::Rgb(70, 70, 70)))
             .border_type(BorderType::Rounded)
             .borders(Borders::ALL);
         frame.render_widget(b, rect);
+
+        if self.search_field.is_empty() {
+            return;
+        }
+        let matches = self.file_viewer.matches();
+        let status = match matches.current() {
+            _ if self.search_worker.is_some() => {
+                let progress = self.search_worker.as_ref().unwrap().progress();
+                format!("{} matches {progress:>3}%", matches.len())
+            }
+            _ if matches.is_empty() => String::from("no matches"),
+            Some(i) => format!("{}/{}", i + 1, matches.len()),
+            None => format!("{} matches", matches.len()),
+        };
+        let layout =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Length(status.len() as u16)])
+                .spacing(1)
+                .horizontal_margin(2)
+                .vertical_margin(1)
+                .areas::<2>(rect);
+        frame.render_widget(
+            Line::from(self.search_field.as_str()).fg(Color::Gray),
+            layout[0],
+        );
+        frame.render_widget(Line::from(status).fg(Color::LightCyan), layout[1]);
+    }
+
+    // Drops down below the search bar, listing the matches around the selected one
+    fn render_search_results(&self, search_bar: Rect, frame: &mut Frame) {
+        let matches = self.file_viewer.matches();
+        let height = (matches.len() as u16 + 2)
+            .min(17)
+            .min(frame.area().height.saturating_sub(search_bar.bottom()));
+        let rect = Rect {
+            y: search_bar.bottom(),
+            height,
+            ..search_bar
+        };
+        let visible = height.saturating_sub(2) as usize;
+        let selected = matches.current().unwrap_or(0);
+        let first = selected
+            .saturating_sub(visible / 2)
+            .min(matches.len().saturating_sub(visible));
+
+        let source = self.file_viewer.buffer();
+        let items = matches.ranges()[first..].iter().take(visible).map(|m| {
+            let bytes = source.read(m.start, (m.end - m.start).min(16));
+            let hex: String = bytes.iter().map(|b| format!("{b:02X} ")).collect();
+            let text: String = bytes
+                .iter()
+                .map(|&b| match b {
+                    b' '..=b'~' => b as char,
+                    _ => '.',
+                })
+                .collect();
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:08X}  ", m.start),
+                    Style::default().fg(Color::LightCyan),
+                ),
+                Span::styled(format!("{hex:<49}"), Style::default().fg(Color::Yellow)),
+                Span::styled(text, Style::default().fg(Color::Green)),
+            ]))
+        });
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(format!(" Results ({}) ", matches.len()))
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .border_type(BorderType::Rounded)
+                    .borders(Borders::ALL),
+            )
+            .highlight_style(Style::new().reversed());
+        let mut state = ListState::default().with_selected(Some(selected - first));
+        frame.render_widget(Clear, rect);
+        frame.render_stateful_widget(list, rect, &mut state);
+    }
+
+    fn render_prompt(&self, title: &str, input: &str, rect: Rect, frame: &mut Frame) {
+        let b = Block::default()
+            .title(title)
+            .border_style(Style::default().fg(Color::Cyan))
+            .border_type(BorderType::Rounded)
+            .borders(Borders::ALL);
+        frame.render_widget(b, rect);
+
+        let mut line = Line::from(vec![
+            Span::styled(input.to_string(), Style::default().fg(Color::LightYellow)),
+            Span::styled(" ", Style::default().reversed()),
+        ]);
+        if let Some(err) = &self.prompt_error {
+            line.push_span(Span::styled(
+                format!("  {err}"),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        frame.render_widget(
+            line,
+            rect.inner(Margin {
+                horizontal: 2,
+                vertical: 1,
+            }),
+        );
     }
 
     fn render_display_buttons(&self, rect: Rect, frame: &mut Frame) {
@@ -254,23 +1344,25 @@ impl ViewerContainer {
         frame.render_widget(b, rect);
 
         use Constraint::Length;
-        let btn_layout = Layout::horizontal([Length(11), Length(15)])
+        let btn_layout = Layout::horizontal([Length(7); 4])
             .flex(Flex::SpaceBetween)
             .vertical_margin(1)
             .horizontal_margin(2)
             .split(rect);
-        let (btn1, btn2) = match self.display_type {
-            DisplayType::Decimal => (
-                render_button("Decimal".to_string(), Color::Green, Color::Black),
-                render_button("HexaDecimal".to_string(), Color::Yellow, Color::Black),
-            ),
-            DisplayType::HexaDecimal => (
-                render_button("Decimal".to_string(), Color::Yellow, Color::Black),
-                render_button("HexaDecimal".to_string(), Color::Green, Color::Black),
-            ),
-        };
-        frame.render_widget(btn1, btn_layout[0]);
-        frame.render_widget(btn2, btn_layout[1]);
+        let buttons = [
+            (DisplayType::Decimal, "Dec"),
+            (DisplayType::HexaDecimal, "Hex"),
+            (DisplayType::Octal, "Oct"),
+            (DisplayType::Binary, "Bin"),
+        ];
+        for (i, (display_type, name)) in buttons.into_iter().enumerate() {
+            let btn = if self.display_type == display_type {
+                render_button(name.to_string(), Color::Green, Color::Black)
+            } else {
+                render_button(name.to_string(), Color::Yellow, Color::Black)
+            };
+            frame.render_widget(btn, btn_layout[i]);
+        }
     }
 
     fn render_endianness_buttons(&self, rect: Rect, frame: &mut Frame) {
@@ -287,7 +1379,12 @@ impl ViewerContainer {
             .vertical_margin(1)
             .horizontal_margin(2)
             .split(rect);
+        // Byte order has no effect on single byte values
         let (btn1, btn2) = match self.endianness {
+            _ if self.data_type.size() == 1 => (
+                render_button("Little".to_string(), Color::DarkGray, Color::Black),
+                render_button("Big".to_string(), Color::DarkGray, Color::Black),
+            ),
             Endianness::Little => (
                 render_button("Little".to_string(), Color::Green, Color::Black),
                 render_button("Big".to_string(), Color::Yellow, Color::Black),
@@ -306,7 +1403,10 @@ impl ViewerContainer {
             .border_style(Style::default().fg(Color::Cyan))
             .border_type(BorderType::Rounded)
             .borders(Borders::ALL)
-            .title(Line::from(" Data Type "));
+            .title(match self.file_viewer.shift() {
+                0 => Line::from(" Data Type "),
+                shift => Line::from(format!(" Data Type (+{shift}) ")),
+            });
         frame.render_widget(b, rect);
 
         use Constraint::Length;
@@ -0,0 +1,595 @@
+use super::common_dt::{DataType, Endianness};
+use super::edit_buffer::Contents;
+use crate::utils::parse_int;
+use regex::bytes::{Regex, RegexBuilder};
+use std::{
+    ops::{Range, RangeInclusive},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, TryRecvError},
+    },
+    thread,
+};
+
+#[cfg(not(test))]
+const CHUNK_SIZE: usize = 4 << 20;
+// Small enough for tests to put matches across chunk boundaries
+#[cfg(test)]
+const CHUNK_SIZE: usize = 16;
+// Regex matches longer than this may be missed where they cross a chunk boundary
+const MAX_TEXT_MATCH: usize = 4096;
+
+#[derive(Debug)]
+pub enum SearchQuery {
+    // `None` is a `??` wildcard
+    Bytes(Vec<Option<u8>>),
+    Value {
+        data_type: DataType,
+        endianness: Endianness,
+        range: ValueRange,
+    },
+    Text(Regex),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum TextEncoding {
+    Ascii,
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+}
+
+#[derive(Debug)]
+pub enum ValueRange {
+    Int(RangeInclusive<i128>),
+    Float(RangeInclusive<f64>),
+}
+
+impl SearchQuery {
+    // `DE AD ?? EF` searches bytes, `=1000`, `=10..20` or `=1.0~0.01` search values of the
+    // current data type and byte order, `"text"` and `/regex/` search strings. Strings take
+    // trailing options: `i` to ignore case and `ascii`, `utf8`, `utf16le` or `utf16be`.
+    pub fn parse(input: &str, data_type: DataType, endianness: Endianness) -> Result<Self, String> {
+        let input = input.trim();
+        if let Some(value) = input.strip_prefix('=') {
+            parse_value(value, data_type, endianness)
+        } else if let Some(text) = input.strip_prefix('"') {
+            parse_text(text, '"')
+        } else if let Some(text) = input.strip_prefix('/') {
+            parse_text(text, '/')
+        } else {
+            parse_bytes(input)
+        }
+    }
+
+    fn max_match_len(&self) -> usize {
+        match self {
+            SearchQuery::Bytes(pattern) => pattern.len(),
+            SearchQuery::Value { data_type, .. } => data_type.size(),
+            SearchQuery::Text(_) => MAX_TEXT_MATCH,
+        }
+    }
+
+    pub fn find_all(&self, data: &[u8]) -> Vec<Range<usize>> {
+        match self {
+            SearchQuery::Bytes(pattern) => data
+                .windows(pattern.len())
+                .enumerate()
+                .filter(|(_, window)| {
+                    window
+                        .iter()
+                        .zip(pattern)
+                        .all(|(b, p)| p.is_none_or(|p| p == *b))
+                })
+                .map(|(i, _)| i..i + pattern.len())
+                .collect(),
+            SearchQuery::Value {
+                data_type,
+                endianness,
+                range,
+            } => data
+                .windows(data_type.size())
+                .enumerate()
+                .filter(|(_, window)| match range {
+                    ValueRange::Int(range) => {
+                        range.contains(&data_type.decode_int(window, endianness))
+                    }
+                    ValueRange::Float(range) => {
+                        range.contains(&data_type.decode_float(window, endianness))
+                    }
+                })
+                .map(|(i, _)| i..i + data_type.size())
+                .collect(),
+            SearchQuery::Text(regex) => regex
+                .find_iter(data)
+                .filter(|m| !m.is_empty())
+                .map(|m| m.range())
+                .collect(),
+        }
+    }
+}
+
+fn parse_bytes(input: &str) -> Result<SearchQuery, String> {
+    let mut pattern = vec![];
+    for token in input.split_whitespace() {
+        if token.len() % 2 != 0 {
+            return Err(format!("odd number of digits in '{token}'"));
+        }
+        for pair in token.as_bytes().chunks(2) {
+            let pair = std::str::from_utf8(pair).map_err(|_| format!("invalid '{token}'"))?;
+            if pair == "??" {
+                pattern.push(None);
+            } else {
+                let byte =
+                    u8::from_str_radix(pair, 16).map_err(|_| format!("invalid byte '{pair}'"))?;
+                pattern.push(Some(byte));
+            }
+        }
+    }
+    if pattern.is_empty() {
+        return Err(String::from("empty pattern"));
+    }
+    if pattern.iter().all(Option::is_none) {
+        return Err(String::from("pattern has only wildcards"));
+    }
+    Ok(SearchQuery::Bytes(pattern))
+}
+
+fn parse_value(
+    value: &str,
+    data_type: DataType,
+    endianness: Endianness,
+) -> Result<SearchQuery, String> {
+    let range = if data_type.is_float() {
+        let range = parse_range(value, parse_float)?;
+        // Values of an f32 view are widened from f32, so the ends are too and `=0.1` finds 0.1f32
+        ValueRange::Float(match data_type {
+            DataType::F32 => (*range.start() as f32 as f64)..=(*range.end() as f32 as f64),
+            _ => range,
+        })
+    } else {
+        let range = parse_range(value, parse_int)?;
+        let (min, max) = data_type.int_range().unwrap();
+        if *range.start() > max || *range.end() < min {
+            return Err(format!("out of range for {data_type}"));
+        }
+        ValueRange::Int(range)
+    };
+    Ok(SearchQuery::Value {
+        data_type,
+        endianness,
+        range,
+    })
+}
+
+fn parse_text(input: &str, delimiter: char) -> Result<SearchQuery, String> {
+    let Some((text, options)) = input.rsplit_once(delimiter) else {
+        return Err(format!("missing closing {delimiter}"));
+    };
+    if text.is_empty() {
+        return Err(String::from("empty pattern"));
+    }
+    let mut ignore_case = false;
+    let mut encoding = TextEncoding::Utf8;
+    for option in options.split_whitespace() {
+        match option.to_lowercase().as_str() {
+            "i" => ignore_case = true,
+            "ascii" => encoding = TextEncoding::Ascii,
+            "utf8" => encoding = TextEncoding::Utf8,
+            "utf16le" => encoding = TextEncoding::Utf16Le,
+            "utf16be" => encoding = TextEncoding::Utf16Be,
+            _ => return Err(format!("unknown option '{option}'")),
+        }
+    }
+
+    let is_regex = delimiter == '/';
+    let pattern = match encoding {
+        TextEncoding::Ascii if !text.is_ascii() => {
+            return Err(String::from("text is not ASCII"));
+        }
+        TextEncoding::Ascii | TextEncoding::Utf8 if is_regex => text.to_string(),
+        TextEncoding::Ascii | TextEncoding::Utf8 => regex::escape(text),
+        _ if is_regex => return Err(String::from("regex supports only ASCII and UTF-8")),
+        TextEncoding::Utf16Le | TextEncoding::Utf16Be => {
+            utf16_pattern(text, ignore_case, encoding == TextEncoding::Utf16Be)
+        }
+    };
+    let utf16 = matches!(encoding, TextEncoding::Utf16Le | TextEncoding::Utf16Be);
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(ignore_case && !utf16)
+        .unicode(encoding == TextEncoding::Utf8)
+        .build()
+        .map_err(|err| {
+            err.to_string()
+                .lines()
+                .last()
+                .unwrap_or_default()
+                .to_string()
+        })?;
+    Ok(SearchQuery::Text(regex))
+}
+
+// Byte level pattern for UTF-16 text, with every case variant of a char as an alternative
+fn utf16_pattern(text: &str, ignore_case: bool, big_endian: bool) -> String {
+    let mut pattern = String::new();
+    for c in text.chars() {
+        let mut variants = vec![c];
+        if ignore_case {
+            let lower: Vec<char> = c.to_lowercase().collect();
+            let upper: Vec<char> = c.to_uppercase().collect();
+            for mapped in [lower, upper] {
+                if let [v] = mapped[..] {
+                    variants.push(v);
+                }
+            }
+            variants.sort();
+            variants.dedup();
+        }
+        let alternatives: Vec<String> = variants
+            .iter()
+            .map(|v| {
+                let mut units = [0u16; 2];
+                v.encode_utf16(&mut units)
+                    .iter()
+                    .flat_map(|u| match big_endian {
+                        true => u.to_be_bytes(),
+                        false => u.to_le_bytes(),
+                    })
+                    .map(|b| format!("\\x{b:02X}"))
+                    .collect()
+            })
+            .collect();
+        pattern.push_str(&format!("(?:{})", alternatives.join("|")));
+    }
+    pattern
+}
+
+fn parse_float(input: &str) -> Result<f64, String> {
+    input
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid number '{}'", input.trim()))
+}
+
+// Ends of `value~epsilon`, integers report overflow instead of wrapping around
+trait RangeBound: Copy + PartialOrd {
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+}
+
+impl RangeBound for i128 {
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        i128::checked_add(self, rhs)
+    }
+    fn checked_sub(self, rhs: Self) -> Option<Self> {
+        i128::checked_sub(self, rhs)
+    }
+}
+
+impl RangeBound for f64 {
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        Some(self + rhs)
+    }
+    fn checked_sub(self, rhs: Self) -> Option<Self> {
+        Some(self - rhs)
+    }
+}
+
+// `lo..hi`, `value~epsilon` or a single value
+fn parse_range<T, F>(input: &str, parse: F) -> Result<RangeInclusive<T>, String>
+where
+    T: RangeBound,
+    F: Fn(&str) -> Result<T, String>,
+{
+    let range = if let Some((lo, hi)) = input.split_once("..") {
+        parse(lo)?..=parse(hi)?
+    } else if let Some((value, epsilon)) = input.split_once('~') {
+        let (value, epsilon) = (parse(value)?, parse(epsilon)?);
+        let lo = value.checked_sub(epsilon).ok_or("overflow")?;
+        let hi = value.checked_add(epsilon).ok_or("overflow")?;
+        lo..=hi
+    } else {
+        let value = parse(input)?;
+        value..=value
+    };
+    if range.start() > range.end() {
+        return Err(String::from("empty range"));
+    }
+    Ok(range)
+}
+
+pub enum SearchMessage {
+    Found(Vec<Range<usize>>),
+    Progress(u8),
+    Done,
+}
+
+#[derive(Debug)]
+pub struct SearchWorker {
+    receiver: Receiver<SearchMessage>,
+    cancel: Arc<AtomicBool>,
+    progress: u8,
+}
+
+impl SearchWorker {
+    // Scans the source in chunks on a separate thread, streaming matches back as they are found
+    pub fn spawn(query: SearchQuery, source: Contents) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let cancelled = cancel.clone();
+        thread::spawn(move || {
+            let len = source.len();
+            let overlap = query.max_match_len() - 1;
+            let mut start = 0;
+            let mut resume = 0;
+            while start < len {
+                if cancelled.load(Ordering::Relaxed) {
+                    return;
+                }
+                let end = (start + CHUNK_SIZE).min(len);
+                // A regex goes on after the last match of the previous chunk, as it would in one
+                // pass over everything, rather than matching the tail of that match again
+                let from = start.max(resume).min(end);
+                let found: Vec<Range<usize>> = query
+                    .find_all(&source.read(from, end - from + overlap))
+                    .into_iter()
+                    .map(|m| m.start + from..m.end + from)
+                    .filter(|m| m.start < end)
+                    .collect();
+                // Regex matches never overlap, the other kinds may
+                if let (SearchQuery::Text(_), Some(last)) = (&query, found.last()) {
+                    resume = last.end;
+                }
+                if !found.is_empty() && sender.send(SearchMessage::Found(found)).is_err() {
+                    return;
+                }
+                let progress = (end as u128 * 100 / len as u128) as u8;
+                if sender.send(SearchMessage::Progress(progress)).is_err() {
+                    return;
+                }
+                start = end;
+            }
+            let _ = sender.send(SearchMessage::Done);
+        });
+        Self {
+            receiver,
+            cancel,
+            progress: 0,
+        }
+    }
+
+    pub fn progress(&self) -> u8 {
+        self.progress
+    }
+
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    // Next message without blocking, `Done` also when the worker went away
+    pub fn poll(&mut self) -> Option<SearchMessage> {
+        match self.receiver.try_recv() {
+            Ok(SearchMessage::Progress(progress)) => {
+                self.progress = progress;
+                Some(SearchMessage::Progress(progress))
+            }
+            Ok(msg) => Some(msg),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(SearchMessage::Done),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Matches {
+    ranges: Vec<Range<usize>>,
+    current: Option<usize>,
+}
+
+impl Matches {
+    pub fn extend(&mut self, ranges: Vec<Range<usize>>) {
+        self.ranges.extend(ranges);
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    pub fn ranges(&self) -> &[Range<usize>] {
+        &self.ranges
+    }
+
+    pub fn select(&mut self, idx: usize) -> Option<usize> {
+        let range = self.ranges.get(idx)?;
+        self.current = Some(idx);
+        Some(range.start)
+    }
+
+    // First match starting after `offset` when nothing is selected yet, wrapping around the end
+    pub fn next(&mut self, offset: usize) -> Option<usize> {
+        if self.ranges.is_empty() {
+            return None;
+        }
+        let idx = match self.current {
+            Some(i) => (i + 1) % self.ranges.len(),
+            None => self.ranges.partition_point(|m| m.start < offset) % self.ranges.len(),
+        };
+        self.current = Some(idx);
+        Some(self.ranges[idx].start)
+    }
+
+    pub fn prev(&mut self, offset: usize) -> Option<usize> {
+        if self.ranges.is_empty() {
+            return None;
+        }
+        let idx = match self.current {
+            Some(i) => i.checked_sub(1),
+            None => self
+                .ranges
+                .partition_point(|m| m.start < offset)
+                .checked_sub(1),
+        }
+        .unwrap_or(self.ranges.len() - 1);
+        self.current = Some(idx);
+        Some(self.ranges[idx].start)
+    }
+
+    // Whether `range` overlaps a match, and whether that match is the selected one
+    pub fn hit(&self, range: Range<usize>) -> Option<bool> {
+        let first = self.ranges.partition_point(|m| m.end <= range.start);
+        self.ranges[first..]
+            .iter()
+            .take_while(|m| m.start < range.end)
+            .enumerate()
+            .map(|(i, _)| Some(first + i) == self.current)
+            .reduce(|a, b| a || b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{byte_source::ByteSource, edit_buffer::EditBuffer};
+    use super::*;
+
+    fn find(input: &str, data: &[u8]) -> Vec<Range<usize>> {
+        SearchQuery::parse(input, DataType::U8, Endianness::Little)
+            .unwrap()
+            .find_all(data)
+    }
+
+    fn parse_error(input: &str) -> Option<String> {
+        SearchQuery::parse(input, DataType::U8, Endianness::Little).err()
+    }
+
+    // Every match the worker reports over `data`, in the order they were sent
+    fn search(input: &str, data: &[u8]) -> Vec<Range<usize>> {
+        let query = SearchQuery::parse(input, DataType::U8, Endianness::Little).unwrap();
+        let source = ByteSource::Owned(Arc::new(data.to_vec()));
+        let mut worker = SearchWorker::spawn(query, EditBuffer::new(source).contents());
+        let mut found = Vec::new();
+        loop {
+            match worker.poll() {
+                Some(SearchMessage::Found(ranges)) => found.extend(ranges),
+                Some(SearchMessage::Done) => return found,
+                _ => thread::yield_now(),
+            }
+        }
+    }
+
+    #[test]
+    fn utf16_text() {
+        let le = b"-h\0i\0-";
+        let be = b"-\0h\0i-";
+        assert_eq!(find("\"hi\" utf16le", le), vec![1..5]);
+        assert!(find("\"hi\" utf16le", be).is_empty());
+        assert_eq!(find("\"hi\" utf16be", be), vec![1..5]);
+        // Surrogate pairs
+        assert_eq!(
+            find("\"\u{1F600}\" utf16be", b"\xD8\x3D\xDE\x00"),
+            vec![0..4]
+        );
+    }
+
+    #[test]
+    fn utf16_text_ignoring_case() {
+        let data = b"H\0i\0h\0I\0\xC9\0\xE9\0";
+        assert_eq!(find("\"hi\" i utf16le", data), vec![0..4, 4..8]);
+        assert!(find("\"hi\" utf16le", data).is_empty());
+        assert_eq!(find("\"\u{E9}\" i utf16le", data), vec![8..10, 10..12]);
+        assert_eq!(find("\"\u{E9}\" utf16le", data), vec![10..12]);
+        assert_eq!(
+            parse_error("/hi/ utf16le"),
+            Some(String::from("regex supports only ASCII and UTF-8"))
+        );
+    }
+
+    #[test]
+    fn ascii_and_utf8_text() {
+        let data = "ab AB \u{E9}".as_bytes();
+        assert_eq!(find("\"ab\"", data), vec![0..2]);
+        assert_eq!(find("\"AB\" i ascii", data), vec![0..2, 3..5]);
+        assert_eq!(find("\"\u{C9}\" i", data), vec![6..8]);
+        assert_eq!(find("\"a.\"", b"ab a."), vec![3..5]);
+        // Without Unicode any byte is a character
+        assert_eq!(find("/./ ascii", b"\xFF"), vec![0..1]);
+        assert!(find("/./", b"\xFF").is_empty());
+        assert_eq!(
+            parse_error("\"\u{E9}\" ascii"),
+            Some(String::from("text is not ASCII"))
+        );
+        assert_eq!(
+            parse_error("\"ab\" utf32"),
+            Some(String::from("unknown option 'utf32'"))
+        );
+        assert_eq!(
+            parse_error("\"ab"),
+            Some(String::from("missing closing \""))
+        );
+    }
+
+    #[test]
+    fn worker_finds_matches_across_chunks() {
+        let mut data = vec![b'.'; 40];
+        data[14..18].copy_from_slice(b"ABCD");
+        assert_eq!(search("41 42 43 44", &data), vec![14..18]);
+        // Overlapping byte matches are all reported, each once
+        assert_eq!(search("42 ?? 44", &data), vec![15..18]);
+        data[20..24].copy_from_slice(b"xxxx");
+        assert_eq!(search("78 78", &data), vec![20..22, 21..23, 22..24]);
+    }
+
+    #[test]
+    fn worker_drops_regex_matches_repeated_by_the_next_chunk() {
+        let mut data = vec![b'.'; 60];
+        // Crosses the boundaries at 16 and 32, the later chunks see its tail again
+        data[10..35].fill(b'A');
+        data[40..50].fill(b'A');
+        assert_eq!(search("/A+/", &data), vec![10..35, 40..50]);
+        assert_eq!(
+            search("/A{5}/", &data),
+            vec![10..15, 15..20, 20..25, 25..30, 30..35, 40..45, 45..50]
+        );
+    }
+
+    #[test]
+    fn value_range_overflow_is_an_error() {
+        fn parse(input: &str) -> Result<SearchQuery, String> {
+            SearchQuery::parse(input, DataType::I64, Endianness::Little)
+        }
+        let max = i128::MAX;
+        assert_eq!(
+            parse(&format!("={max}~1")).err(),
+            Some(String::from("overflow"))
+        );
+        assert_eq!(
+            parse(&format!("=-{max}~2")).err(),
+            Some(String::from("overflow"))
+        );
+        assert!(parse("=10~2").is_ok());
+    }
+
+    #[test]
+    fn f32_values_match_as_shown() {
+        let data: Vec<u8> = [0.1f32, 0.2, 0.3]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let find = |input: &str| {
+            SearchQuery::parse(input, DataType::F32, Endianness::Little)
+                .unwrap()
+                .find_all(&data)
+        };
+        assert_eq!(find("=0.1"), vec![0..4]);
+        assert_eq!(find("=0.2~0.1"), vec![0..4, 4..8, 8..12]);
+        assert_eq!(find("=0.15..0.3"), vec![4..8, 8..12]);
+    }
+}
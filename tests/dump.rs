@@ -0,0 +1,73 @@
+// Golden tests: `dump` output of tests/fixtures/sample.bin compared with checked in dumps. The xxd
+// fixtures were written by xxd itself, the hexdump ones follow `hexdump -C`.
+use std::process::Command;
+
+const SAMPLE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sample.bin");
+
+fn dump(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_hexer"))
+        .arg("dump")
+        .args(args)
+        .arg(SAMPLE)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn xxd() {
+    assert_eq!(dump(&["-f", "xxd"]), include_str!("fixtures/sample.xxd"));
+}
+
+#[test]
+fn xxd_cols() {
+    let cases = [
+        ("13", include_str!("fixtures/sample.c13.xxd")),
+        ("8", include_str!("fixtures/sample.c8.xxd")),
+        ("1", include_str!("fixtures/sample.c1.xxd")),
+    ];
+    for (cols, expected) in cases {
+        assert_eq!(dump(&["-f", "xxd", "-c", cols]), expected, "-c {cols}");
+    }
+}
+
+#[test]
+fn xxd_seek() {
+    assert_eq!(
+        dump(&["-f", "xxd", "-s", "5"]),
+        include_str!("fixtures/sample.s5.xxd")
+    );
+}
+
+#[test]
+fn hexdump() {
+    assert_eq!(
+        dump(&["-f", "hexdump"]),
+        include_str!("fixtures/sample.hexdump")
+    );
+}
+
+#[test]
+fn hexdump_seek() {
+    assert_eq!(
+        dump(&["-f", "hexdump", "-s", "5"]),
+        include_str!("fixtures/sample.s5.hexdump")
+    );
+}
+
+#[test]
+fn hexer_partial_cell() {
+    assert_eq!(
+        dump(&["-t", "u32", "-d", "hex"]),
+        include_str!("fixtures/sample.u32.hexer")
+    );
+    assert_eq!(
+        dump(&["-t", "i16", "-e", "big", "-s", "1", "-n", "9"]),
+        include_str!("fixtures/sample.i16be.hexer")
+    );
+}
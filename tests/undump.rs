@@ -0,0 +1,86 @@
+// Round trips: `dump` output of tests/fixtures/sample.bin in every format is rebuilt by `undump`,
+// which detects the format from the first line.
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+const SAMPLE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sample.bin");
+const SAMPLE_BYTES: &[u8] = include_bytes!("fixtures/sample.bin");
+
+fn hexer(args: &[&str], input: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_hexer"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn dump(args: &[&str]) -> Vec<u8> {
+    let output = hexer(&[&["dump"], args, &[SAMPLE]].concat(), &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output.stdout
+}
+
+fn undump(dump: &[u8], args: &[&str]) -> Result<Vec<u8>, String> {
+    let output = hexer(&[&["undump"], args].concat(), dump);
+    match output.status.success() {
+        true => Ok(output.stdout),
+        false => Err(String::from_utf8_lossy(&output.stderr).into_owned()),
+    }
+}
+
+#[test]
+fn round_trip() {
+    let cases: [&[&str]; 9] = [
+        &["-f", "xxd"],
+        &["-f", "xxd", "-c", "13"],
+        // Squeezes the zero lines into `*`
+        &["-f", "hexdump"],
+        &["-d", "hex"],
+        &["-d", "oct"],
+        &["-d", "bin"],
+        // Multi-byte cells end in a partial `??` cell
+        &["-t", "u32", "-d", "hex"],
+        &["-t", "i16", "-e", "big", "-d", "oct"],
+        &["-t", "u64", "-d", "bin", "-c", "3"],
+    ];
+    for args in cases {
+        assert_eq!(
+            undump(&dump(args), &[]).as_deref(),
+            Ok(SAMPLE_BYTES),
+            "{args:?}"
+        );
+    }
+}
+
+#[test]
+fn gap_before_first_line_is_zero_filled() {
+    let mut expected = vec![0; 5];
+    expected.extend_from_slice(&SAMPLE_BYTES[5..]);
+    for format in ["xxd", "hexdump"] {
+        let dump = dump(&["-f", format, "-s", "5"]);
+        assert_eq!(undump(&dump, &[]), Ok(expected.clone()), "{format}");
+        let err = undump(&dump, &["--strict"]).unwrap_err();
+        assert!(
+            err.contains("line 1: expected address 0x0, found 0x5"),
+            "{format}: {err}"
+        );
+    }
+}
+
+#[test]
+fn overlapping_address_is_rejected() {
+    let dump = b"00000000: 4142 4344  ABCD\n00000002: 45  E\n";
+    let err = undump(dump, &[]).unwrap_err();
+    assert!(
+        err.contains("line 2: address 0x2 overlaps the previous line ending at 0x4"),
+        "{err}"
+    );
+}